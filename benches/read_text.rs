@@ -0,0 +1,40 @@
+//! Measure `TextReader` throughput on all-ASCII input, which takes the ASCII
+//! fast path, and on mostly non-ASCII input, which doesn't.
+//!
+//! Run with `RUSTFLAGS="--cfg bench" cargo +nightly bench`.
+
+#![cfg(bench)]
+#![feature(test)]
+
+extern crate test;
+
+use basic_text::TextReader;
+use std::io::Read;
+use test::Bencher;
+
+fn read(b: &mut Bencher, input: &str) {
+    b.bytes = input.len() as u64;
+    b.iter(|| {
+        let mut s = String::new();
+        TextReader::new(input.as_bytes())
+            .read_to_string(&mut s)
+            .unwrap();
+        s
+    });
+}
+
+#[bench]
+fn read_ascii(b: &mut Bencher) {
+    read(
+        b,
+        &"2021-01-01T00:00:00Z INFO server: request handled in 12ms\n".repeat(2048),
+    );
+}
+
+#[bench]
+fn read_non_ascii(b: &mut Bencher) {
+    read(
+        b,
+        &"Καλημέρα κόσμε, こんにちは世界, Grüß Gott, Ångström\n".repeat(2048),
+    );
+}
@@ -0,0 +1,42 @@
+//! Compare writing a `TextStr` with writing the same contents as a
+//! `TextSubstr`, which can't skip the starter check or the temporary buffer.
+//!
+//! Run with `RUSTFLAGS="--cfg bench" cargo +nightly bench`.
+
+#![cfg(bench)]
+#![feature(test)]
+
+extern crate test;
+
+use basic_text::{TextStr, TextWriter, WriteText};
+use layered_io::WriteLayered;
+use std::io::sink;
+use test::Bencher;
+
+fn sample() -> String {
+    "The quick brown fox jumps over the lazy dog. \u{c5}ngstr\u{f6}m 漢字\n".repeat(1024)
+}
+
+#[bench]
+fn write_text(b: &mut Bencher) {
+    let s = sample();
+    let text = TextStr::from_text(&s).unwrap();
+    b.bytes = s.len() as u64;
+    b.iter(|| {
+        let mut writer = TextWriter::new(sink());
+        writer.write_text(text).unwrap();
+        writer.close().unwrap();
+    });
+}
+
+#[bench]
+fn write_text_substr(b: &mut Bencher) {
+    let s = sample();
+    let text = TextStr::from_text(&s).unwrap();
+    b.bytes = s.len() as u64;
+    b.iter(|| {
+        let mut writer = TextWriter::new(sink());
+        writer.write_text_substr(text.as_ref()).unwrap();
+        writer.close().unwrap();
+    });
+}
@@ -0,0 +1,158 @@
+use crate::input_state::{guard_end, push_ground_char, InputState as State};
+use crate::stream_safe_nfc::StreamSafeNfc;
+use crate::unicode::{BOM, ESC};
+use std::collections::{vec_deque, VecDeque};
+use std::iter::Fuse;
+use std::mem::take;
+
+/// An iterator which translates an arbitrary sequence of scalar values into
+/// a Basic Text sequence, in the same way that `TextReader` translates an
+/// input stream.
+pub struct BasicTextChars<I: Iterator<Item = char>> {
+    iter: Fuse<I>,
+
+    /// Scalar values which have been translated but not normalized yet.
+    queue: VecDeque<char>,
+
+    /// An iterator over normalized scalar values.
+    ssnfc_iter: StreamSafeNfc<vec_deque::IntoIter<char>>,
+
+    /// At the beginning of a stream, expect a normalization-form starter.
+    expect_starter: bool,
+
+    /// For ignoring BOM at the start of a stream.
+    at_start: bool,
+
+    /// Set once the underlying iterator is exhausted.
+    at_end: bool,
+
+    /// Control-code and escape-sequence state machine.
+    state: State,
+}
+
+impl<I: Iterator<Item = char>> BasicTextChars<I> {
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: Iterator::fuse(iter),
+            queue: VecDeque::new(),
+            ssnfc_iter: StreamSafeNfc::new(VecDeque::new().into_iter()),
+            expect_starter: true,
+            at_start: true,
+            at_end: false,
+            state: State::Ground(true),
+        }
+    }
+
+    /// Translate scalar values into the queue up to the next newline, which
+    /// is a boundary at which normalization can safely restart.
+    fn fill(&mut self) {
+        while let Some(c) = self.iter.next() {
+            // If we're at the start of a stream, skip over a leading BOM.
+            if take(&mut self.at_start) && c == BOM {
+                continue;
+            }
+
+            self.process(c);
+
+            if self.queue.back() == Some(&'\n') {
+                return;
+            }
+        }
+
+        self.finish();
+        self.at_end = true;
+    }
+
+    fn process(&mut self, c: char) {
+        loop {
+            match (self.state, c) {
+                (State::Ground(_), c) => match c {
+                    '\n' => {
+                        self.queue.push_back('\n');
+                        self.expect_starter = false;
+                        self.state = State::Ground(true);
+                    }
+                    '\r' => self.state = State::Cr,
+                    '\x0c' => self.state = State::Ff,
+                    ESC => self.state = State::Esc,
+                    c => {
+                        self.state = State::Ground(false);
+                        push_ground_char(c, &mut self.queue, &mut self.expect_starter);
+                    }
+                },
+
+                (State::Cr, c) => {
+                    self.queue.push_back('\n');
+                    self.expect_starter = false;
+                    self.state = State::Ground(true);
+                    if c != '\n' {
+                        continue;
+                    }
+                }
+
+                (State::Ff, c) => {
+                    if c != '\x0c' {
+                        if c != '\n' && c != '\r' {
+                            self.queue.push_back(' ');
+                        }
+                        self.expect_starter = false;
+                        self.state = State::Ground(false);
+                        continue;
+                    }
+                }
+
+                (_, c) => {
+                    if !self.state.escape_sequence_step(c) {
+                        continue;
+                    }
+                }
+            }
+            break;
+        }
+    }
+
+    fn finish(&mut self) {
+        match self.state {
+            State::Ground(_) => {}
+            State::Cr => {
+                self.queue.push_back('\n');
+                self.state = State::Ground(true);
+            }
+            State::Ff => {
+                self.queue.push_back(' ');
+                self.state = State::Ground(false);
+            }
+            State::Esc | State::CsiStart | State::Csi | State::Osc | State::Linux => {
+                self.state = State::Ground(false);
+            }
+        }
+
+        // If the stream ends in a non-ending char, append a CGJ.
+        guard_end(&mut self.queue);
+
+        // If the stream doesn't end in a newline, append one.
+        if self.state != State::Ground(true) {
+            self.queue.push_back('\n');
+            self.state = State::Ground(true);
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for BasicTextChars<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(c) = self.ssnfc_iter.next() {
+                return Some(c);
+            }
+            if self.at_end {
+                return None;
+            }
+            self.fill();
+            self.ssnfc_iter = StreamSafeNfc::new(take(&mut self.queue).into_iter());
+        }
+    }
+}
@@ -133,6 +133,12 @@ pub enum BasicTextError {
     LineSeparation,
     #[error("Noncharacters are intended for internal use only")]
     NonChar,
+    #[error("Non-ending scalar value not valid at the end of text: {0:?}")]
+    NonEnding(char),
+    #[error("Non-starter not valid at the start of text: {0:?}")]
+    NonStarter(char),
+    #[error("Text is not in Stream-Safe NFC")]
+    NotStreamSafeNfc,
     #[error("Paragraph separation is a rich-text function")]
     ParaSeparation,
     #[error("U+FEFF is not necessary in Basic Text")]
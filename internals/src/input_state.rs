@@ -0,0 +1,121 @@
+//! The control-code and escape-sequence state machine used when translating
+//! input into Basic Text, shared by `TextReader` and `BasicTextChars`.
+
+use crate::replace::replace;
+use crate::text_utils::{is_basic_text_end, is_basic_text_start};
+use crate::unicode::{BEL, CAN, CGJ, DEL, ESC};
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputState {
+    // Default state. Boolean is true iff we just saw a '\n'.
+    Ground(bool),
+
+    // After a '\r'.
+    Cr,
+
+    // After a '\x0c'.
+    Ff,
+
+    // After a '\x1b'.
+    Esc,
+
+    // Immediately after a "\x1b[".
+    CsiStart,
+
+    // Within a sequence started by "\x1b[".
+    Csi,
+
+    // Within a sequence started by "\x1b]".
+    Osc,
+
+    // After a "\x1b[[".
+    Linux,
+}
+
+impl InputState {
+    /// Test whether we're within an escape sequence.
+    #[inline]
+    pub fn in_escape_sequence(self) -> bool {
+        matches!(
+            self,
+            Self::Esc | Self::CsiStart | Self::Csi | Self::Osc | Self::Linux
+        )
+    }
+
+    /// Within an escape sequence, advance the state with `c`. Return `true`
+    /// if `c` is consumed as part of the sequence, or `false` if it ends the
+    /// sequence without being part of it, in which case it should be
+    /// processed again in the new state.
+    #[inline]
+    pub fn escape_sequence_step(&mut self, c: char) -> bool {
+        match (*self, c) {
+            (Self::Esc, '[') => *self = Self::CsiStart,
+            (Self::Esc, ']') => *self = Self::Osc,
+            (Self::Esc, ESC) => *self = Self::Esc,
+            (Self::Esc, '@'..='~' | CAN) => *self = Self::Ground(false),
+            (Self::Esc, _) => {
+                *self = Self::Ground(false);
+                return false;
+            }
+
+            (Self::CsiStart, '[') => *self = Self::Linux,
+            (Self::CsiStart | Self::Csi, ' '..='?') => *self = Self::Csi,
+            (Self::CsiStart | Self::Csi, '@'..='~' | CAN) => *self = Self::Ground(false),
+            (Self::CsiStart | Self::Csi, _) => {
+                *self = Self::Ground(false);
+                return false;
+            }
+
+            (Self::Osc, BEL | CAN) => *self = Self::Ground(false),
+            (Self::Osc, ESC) => *self = Self::Esc,
+            (Self::Osc, _) => (),
+
+            (Self::Linux, '\0'..=DEL) => *self = Self::Ground(false),
+            (Self::Linux, _) => {
+                *self = Self::Ground(false);
+                return false;
+            }
+
+            (Self::Ground(_) | Self::Cr | Self::Ff, _) => {
+                unreachable!("not within an escape sequence")
+            }
+        }
+        true
+    }
+}
+
+/// In the ground state, translate `c`, which isn't a control code with its
+/// own state, onto the end of `queue`. If `expect_starter` is set and the
+/// result starts with a non-starter, prepend a CGJ to guard it, and return
+/// `true`.
+#[inline]
+pub fn push_ground_char(c: char, queue: &mut VecDeque<char>, expect_starter: &mut bool) -> bool {
+    let pos = queue.len();
+    replace(c, queue);
+
+    if std::mem::take(expect_starter)
+        && !queue
+            .get(pos)
+            .copied()
+            .map(is_basic_text_start)
+            .unwrap_or(true)
+    {
+        queue.insert(pos, CGJ);
+        return true;
+    }
+    false
+}
+
+/// At the end of a stream, if `queue` ends in a non-ending scalar value,
+/// append a CGJ, and return `true`.
+#[inline]
+pub fn guard_end(queue: &mut VecDeque<char>) -> bool {
+    if let Some(last) = queue.back() {
+        if !is_basic_text_end(*last) {
+            queue.push_back(CGJ);
+            return true;
+        }
+    }
+    false
+}
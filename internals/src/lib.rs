@@ -1,22 +1,28 @@
 pub mod unicode;
 
+mod basic_text_chars;
 mod categorize;
 mod check;
+mod input_state;
 mod isolate_unassigned;
 mod pre_normalization;
 mod replace;
+mod stream_safe_nfc;
 mod text_utils;
 
 // Re-export `unicode_normalization` so that our users can use the same version
 // we're using.
 pub use unicode_normalization;
 
+pub use basic_text_chars::BasicTextChars;
 pub use categorize::Categorize;
 pub use check::{check_basic_text_char, BasicTextError};
+pub use input_state::{guard_end, push_ground_char, InputState};
 pub use isolate_unassigned::IsolateUnassigned;
 pub use pre_normalization::PreNormalization;
 pub use replace::replace;
+pub use stream_safe_nfc::{is_stream_safe_boundary, stream_safe_boundaries, StreamSafeNfc};
 pub use text_utils::{
-    is_basic_text, is_basic_text_end, is_basic_text_start, is_basic_text_substr,
-    is_basic_text_substr_quick,
+    first_violation, is_basic_text, is_basic_text_end, is_basic_text_start, is_basic_text_substr,
+    is_basic_text_substr_quick, is_default_ignorable, is_extended_pictographic,
 };
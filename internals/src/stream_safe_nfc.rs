@@ -0,0 +1,78 @@
+use crate::isolate_unassigned::IsolateUnassigned;
+use crate::text_utils::is_private_use_area;
+use crate::unicode::CGJ;
+use unicode_normalization::char::is_public_assigned;
+use unicode_normalization::{Recompositions, Replacements, StreamSafe, UnicodeNormalization};
+
+/// An iterator which translates a sequence of scalar values into Stream-Safe
+/// NFC, applying the CJK Compatibility Ideograph standardized variants and
+/// inserting CGJs around unassigned scalar values.
+///
+/// Unlike `BasicTextChars`, this doesn't perform any other translations, so
+/// it can be used to normalize the contents of text which is otherwise
+/// already known to be valid.
+pub struct StreamSafeNfc<I: Iterator<Item = char>> {
+    iter: Recompositions<StreamSafe<Replacements<IsolateUnassigned<I>>>>,
+}
+
+impl<I: Iterator<Item = char>> StreamSafeNfc<I> {
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: IsolateUnassigned::new(iter)
+                .cjk_compat_variants()
+                .stream_safe()
+                .nfc(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for StreamSafeNfc<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Test whether a stream of scalar values can be split immediately after `c`
+/// and the parts normalized with `StreamSafeNfc` independently.
+///
+/// Newlines and CGJs are starters which never compose with anything, and
+/// which reset the Stream-Safe count of non-starters.
+#[inline]
+pub fn is_stream_safe_boundary(c: char) -> bool {
+    matches!(c, '\n' | CGJ)
+}
+
+/// Return an iterator over the byte offsets in `s` at which it can be split
+/// into parts which can be normalized with `StreamSafeNfc` independently,
+/// producing the same result as normalizing `s` as a whole.
+///
+/// The offsets are in increasing order, and are immediately after a newline
+/// or a CGJ. A CGJ followed by an unassigned scalar value isn't a boundary,
+/// as normalization would isolate the unassigned scalar value with a second
+/// CGJ. The start and end of `s` are not included, except that an offset at
+/// the end is produced if `s` ends with a boundary.
+pub fn stream_safe_boundaries(s: &str) -> impl Iterator<Item = usize> + '_ {
+    s.char_indices().filter_map(move |(i, c)| {
+        let end = i + c.len_utf8();
+        if !is_stream_safe_boundary(c) {
+            return None;
+        }
+        if c == CGJ {
+            if let Some(next) = s[end..].chars().next() {
+                if !is_public_assigned(next) && !is_private_use_area(next) {
+                    return None;
+                }
+            }
+        }
+        Some(end)
+    })
+}
@@ -71,6 +71,9 @@ pub const LS: char = '\u{2028}';
 /// PARAGRAPH SEPARATOR
 pub const PS: char = '\u{2029}';
 
+/// DOTTED CIRCLE, conventionally used to display an isolated combining mark
+pub const DOTTED_CIRCLE: char = '\u{25cc}';
+
 // TODO: include ZWJ, WJ, ZWNJ, CGJ as non-starters?
 #[inline]
 pub fn is_normalization_form_starter(c: char) -> bool {
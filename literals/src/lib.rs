@@ -1,7 +1,12 @@
-use basic_text_internals::{is_basic_text, is_basic_text_substr};
+use basic_text_internals::{
+    is_basic_text, is_basic_text_end, is_basic_text_start, is_basic_text_substr,
+};
 use proc_macro::TokenStream;
 use quote::quote_spanned;
-use syn::{parse_macro_input, LitStr};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, LitStr, Token};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// `TextStr` literal support: `text!("string literal")`.
 ///
@@ -44,3 +49,100 @@ pub fn text_substr(input: TokenStream) -> TokenStream {
     })
     .into()
 }
+
+/// Grapheme count of a `TextStr` literal: `text_len!("string literal")`.
+///
+/// Returns a constant `usize` containing the number of extended grapheme
+/// clusters in the provided string literal, which must be Basic Text, as
+/// with `text!`.
+#[proc_macro]
+pub fn text_len(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    let span = input.span();
+    let value = input.value();
+
+    if !is_basic_text(&value) {
+        return (quote_spanned! { span =>
+            compile_error!("string literal is not Basic Text")
+        })
+        .into();
+    }
+
+    let len = value.graphemes(true).count();
+
+    (quote_spanned! { span =>
+        #len
+    })
+    .into()
+}
+
+/// Concatenated `TextStr` literal support:
+/// `text_concat!("string literal", "another string literal", ...)`.
+///
+/// Returns a `'static &TextStr` containing the concatenation of the provided
+/// string literals. The concatenation is checked as a whole, so literals
+/// which are each Basic Text substrings but which don't form Basic Text
+/// when joined, such as `"e"` and `"\u{301}"`, which would need to be
+/// normalized to `"\u{e9}"`, are rejected, reporting the position of the
+/// seam.
+#[proc_macro]
+pub fn text_concat(input: TokenStream) -> TokenStream {
+    let parser = Punctuated::<LitStr, Token![,]>::parse_terminated;
+    let pieces = match parser.parse(input) {
+        Ok(pieces) => pieces,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let mut joined = String::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        let value = piece.value();
+        let span = piece.span();
+
+        // Check each literal on its own first, so that errors within a
+        // literal are reported as such.
+        let valid = is_basic_text_substr(&value)
+            && (i != 0 || !matches!(value.chars().next(), Some(c) if !is_basic_text_start(c)))
+            && (i != pieces.len() - 1
+                || !matches!(value.chars().next_back(), Some(c) if !is_basic_text_end(c)));
+        if !valid {
+            return (quote_spanned! { span =>
+                compile_error!("string literal is not Basic Text")
+            })
+            .into();
+        }
+
+        let seam = joined.len();
+        joined.push_str(&value);
+
+        // The literals so far were valid when joined, so if they aren't now,
+        // the problem is at this seam.
+        if i != 0 && !is_basic_text_substr(&joined) {
+            let message = format!(
+                "concatenated string literal is not Basic Text at the seam at byte {}",
+                seam
+            );
+            return (quote_spanned! { span =>
+                compile_error!(#message)
+            })
+            .into();
+        }
+    }
+
+    let span = match pieces.first() {
+        Some(first) => first.span(),
+        None => proc_macro::Span::call_site().into(),
+    };
+
+    if !is_basic_text(&joined) {
+        return (quote_spanned! { span =>
+            compile_error!("concatenated string literal is not Basic Text")
+        })
+        .into();
+    }
+
+    let joined = LitStr::new(&joined, span);
+    (quote_spanned! { span =>
+        unsafe { ::basic_text::TextStr::from_text_unchecked(#joined) }
+    })
+    .into()
+}
@@ -0,0 +1,204 @@
+//! The `AsyncTextReader` type.
+
+use crate::text_input::TextInput;
+use crate::RepairStats;
+use basic_text_internals::unicode::{NORMALIZATION_BUFFER_SIZE, REPL};
+use layered_io::Status;
+use std::cmp::min;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::pin::Pin;
+use std::str;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// An [`AsyncRead`] implementation which translates from an input
+/// `AsyncRead` implementation producing an arbitrary byte sequence into a
+/// valid Basic Text stream.
+///
+/// This is the asynchronous counterpart of [`TextReader`], and performs the
+/// same translation.
+///
+/// [`TextReader`]: crate::TextReader
+pub struct AsyncTextReader<Inner> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Text translation state.
+    input: TextInput,
+
+    /// Bytes read from `inner` which don't yet form a complete UTF-8
+    /// sequence.
+    incomplete: Vec<u8>,
+
+    /// Translated output which hasn't been returned yet, starting at `pos`.
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<Inner: AsyncRead + Unpin> AsyncTextReader<Inner> {
+    /// Construct a new instance of `AsyncTextReader` wrapping `inner`, which
+    /// can be anything that implements [`AsyncRead`].
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            input: TextInput::new(),
+            incomplete: Vec::new(),
+            buffer: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Return the counts of the repairs performed on the input so far.
+    #[inline]
+    pub fn repair_stats(&self) -> RepairStats {
+        self.input.stats
+    }
+
+    /// Return the underlying stream object, discarding any input which has
+    /// been read but not yet returned.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Decode the bytes in `self.incomplete` which form complete UTF-8
+    /// sequences, replacing invalid sequences with U+FFFD. At the end of the
+    /// stream, an incomplete sequence is replaced too.
+    fn decode(&mut self, end: bool) -> String {
+        let mut raw_string = String::new();
+        let mut rest = &self.incomplete[..];
+        loop {
+            match str::from_utf8(rest) {
+                Ok(s) => {
+                    raw_string.push_str(s);
+                    rest = &[];
+                    break;
+                }
+                Err(error) => {
+                    let (valid, after) = rest.split_at(error.valid_up_to());
+                    // Safety: `from_utf8` validated this part.
+                    raw_string.push_str(unsafe { str::from_utf8_unchecked(valid) });
+                    match error.error_len() {
+                        Some(len) => {
+                            raw_string.push(REPL);
+                            rest = &after[len..];
+                        }
+                        None => {
+                            if end {
+                                raw_string.push(REPL);
+                                rest = &[];
+                            } else {
+                                rest = after;
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        let consumed = self.incomplete.len() - rest.len();
+        self.incomplete.drain(..consumed);
+        raw_string
+    }
+}
+
+impl<Inner: AsyncRead + Unpin> AsyncRead for AsyncTextReader<Inner> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        loop {
+            // If we have translated output, return it.
+            if this.pos < this.buffer.len() {
+                let n = min(buf.remaining(), this.buffer.len() - this.pos);
+                buf.put_slice(&this.buffer[this.pos..this.pos + n]);
+                this.pos += n;
+                return Poll::Ready(Ok(()));
+            }
+
+            this.buffer.resize(NORMALIZATION_BUFFER_SIZE, 0);
+            this.pos = 0;
+
+            let mut nread = 0;
+            if let Some((size, _status)) = this.input.read_queued(&mut this.buffer, &mut nread) {
+                this.buffer.truncate(size);
+                continue;
+            }
+
+            let mut raw_bytes = [0_u8; 4096];
+            let mut raw_buf = ReadBuf::new(&mut raw_bytes);
+            match Pin::new(&mut this.inner).poll_read(cx, &mut raw_buf) {
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(err)) => {
+                    this.buffer.truncate(nread);
+                    return Poll::Ready(Err(err));
+                }
+                Poll::Pending => {
+                    // Return any output we have before waiting.
+                    this.buffer.truncate(nread);
+                    if nread != 0 {
+                        continue;
+                    }
+                    return Poll::Pending;
+                }
+            }
+
+            let status = if raw_buf.filled().is_empty() {
+                Status::End
+            } else {
+                Status::active()
+            };
+            this.incomplete.extend_from_slice(raw_buf.filled());
+            let raw_string = this.decode(status.is_end());
+
+            let (size, status) =
+                this.input
+                    .read_processed(raw_string, status, &mut this.buffer, nread);
+            this.buffer.truncate(size);
+
+            // If there's no output at the end of the stream, report the end.
+            if size == 0 && status.is_end() {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+}
+
+impl<Inner: Debug> Debug for AsyncTextReader<Inner> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("AsyncTextReader");
+        b.field("inner", &self.inner);
+        b.finish()
+    }
+}
+
+#[cfg(test)]
+fn translate(bytes: &[u8]) -> String {
+    use tokio::io::AsyncReadExt;
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let mut reader = AsyncTextReader::new(bytes);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).await.unwrap();
+        s
+    })
+}
+
+#[test]
+fn test_async_read() {
+    assert_eq!(translate(b""), "");
+    assert_eq!(translate(b"hello\r\nworld"), "hello\nworld\n");
+    assert_eq!(translate(b"\x1b[31mred\x07\xff\n"), "red\u{fffd}\u{fffd}\n");
+    assert_eq!(translate("A\u{30a}".as_bytes()), "\u{c5}\n");
+
+    let long = "x\u{301}".repeat(4096);
+    assert_eq!(translate(long.as_bytes()), format!("{}\n", long));
+}
@@ -0,0 +1,263 @@
+//! The `AsyncTextWriter` type.
+
+use crate::text_output::TextOutput;
+use basic_text_internals::unicode::MAX_UTF8_SIZE;
+use std::cmp::min;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::pin::Pin;
+use std::str;
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// An [`AsyncWrite`] implementation which translates to an output
+/// `AsyncWrite` producing a valid Basic Text stream from an arbitrary byte
+/// sequence.
+///
+/// This is the asynchronous counterpart of [`TextWriter`], and performs the
+/// same checks. Translated output is buffered until the next call to
+/// `poll_write`, `poll_flush`, or `poll_shutdown`. The stream must be ended
+/// with `poll_shutdown`, or abandoned with [`abandon_into_inner`].
+///
+/// [`TextWriter`]: crate::TextWriter
+/// [`abandon_into_inner`]: Self::abandon_into_inner
+pub struct AsyncTextWriter<Inner> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Text translation state.
+    output: TextOutput,
+
+    /// Translated output which hasn't been written to `inner` yet.
+    buffer: Vec<u8>,
+
+    /// The start of a UTF-8 sequence split across writes.
+    incomplete: Vec<u8>,
+
+    /// Set once an error has been reported, after which the stream can't be
+    /// written to, flushed, or shut down.
+    failed: bool,
+}
+
+impl<Inner: AsyncWrite + Unpin> AsyncTextWriter<Inner> {
+    /// Construct a new instance of `AsyncTextWriter` wrapping `inner`, which
+    /// can be anything that implements [`AsyncWrite`].
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            output: TextOutput::new(),
+            buffer: Vec::new(),
+            incomplete: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// Like `new`, but enables CRLF output mode, which translates "\n" to
+    /// "\r\n" for compatibility with consumers that need that.
+    #[inline]
+    pub fn with_crlf_compatibility(inner: Inner) -> Self {
+        Self {
+            inner,
+            output: TextOutput::with_crlf_compatibility(),
+            buffer: Vec::new(),
+            incomplete: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// Discard any buffered output and return the underlying stream object.
+    #[inline]
+    pub fn abandon_into_inner(mut self) -> Inner {
+        self.output.reset();
+        self.inner
+    }
+
+    /// Write all the buffered output to the underlying stream.
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.failed {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "text stream used after an error",
+            )));
+        }
+        while !self.buffer.is_empty() {
+            let n = match ready!(Pin::new(&mut self.inner).poll_write(cx, &self.buffer)) {
+                Ok(0) => return Poll::Ready(Err(self.fail(io::ErrorKind::WriteZero.into()))),
+                Ok(n) => n,
+                Err(err) => return Poll::Ready(Err(self.fail(err))),
+            };
+            self.buffer.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Stage `buf` for output, returning the number of bytes consumed.
+    fn stage(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.output.stage(buf) {
+            Ok(n) => {
+                self.output.drain_staged(&mut self.buffer);
+                Ok(n)
+            }
+            Err(err) => Err(self.fail(err)),
+        }
+    }
+
+    /// Complete the UTF-8 sequence split from a previous write with bytes
+    /// from the start of `buf`, and stage it, returning the number of bytes
+    /// of `buf` consumed.
+    fn stage_incomplete(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let prev = self.incomplete.len();
+        let k = min(MAX_UTF8_SIZE - prev, buf.len());
+        self.incomplete.extend_from_slice(&buf[..k]);
+        let len = match str::from_utf8(&self.incomplete) {
+            Ok(_) => self.incomplete.len(),
+            Err(error) if error.valid_up_to() != 0 => error.valid_up_to(),
+            // The sequence is still incomplete, and all of `buf` is consumed.
+            Err(error) if error.error_len().is_none() => return Ok(k),
+            // Let `stage` report the invalid sequence.
+            Err(_) => self.incomplete.len(),
+        };
+        let incomplete = std::mem::take(&mut self.incomplete);
+        let n = self.stage(&incomplete[..len])?;
+        Ok(n - prev)
+    }
+
+    /// Record that an error has occurred, so that the stream isn't ended
+    /// cleanly, and return it.
+    fn fail(&mut self, err: io::Error) -> io::Error {
+        self.output.reset();
+        self.failed = true;
+        err
+    }
+}
+
+impl<Inner: AsyncWrite + Unpin> AsyncWrite for AsyncTextWriter<Inner> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+
+        if !this.incomplete.is_empty() {
+            return Poll::Ready(this.stage_incomplete(buf));
+        }
+
+        // Hold back a UTF-8 sequence split at the end of `buf`, to be
+        // completed by the next write.
+        let (complete, partial) = match str::from_utf8(buf) {
+            Err(error) if error.error_len().is_none() => buf.split_at(error.valid_up_to()),
+            _ => (buf, &[][..]),
+        };
+        if !complete.is_empty() {
+            let n = this.stage(complete)?;
+            if n != complete.len() {
+                return Poll::Ready(Ok(n));
+            }
+        }
+        this.incomplete.extend_from_slice(partial);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+
+        if let Err(err) = this.output.check_flush() {
+            return Poll::Ready(Err(this.fail(err)));
+        }
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+
+        if !this.incomplete.is_empty() {
+            return Poll::Ready(Err(this.fail(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete UTF-8 sequence at end of text stream",
+            ))));
+        }
+        if let Err(err) = this.output.check_end() {
+            return Poll::Ready(Err(this.fail(err)));
+        }
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+impl<Inner: Debug> Debug for AsyncTextWriter<Inner> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("AsyncTextWriter");
+        b.field("inner", &self.inner);
+        b.finish()
+    }
+}
+
+#[cfg(test)]
+fn translate(bytes: &[u8]) -> io::Result<String> {
+    use tokio::io::AsyncWriteExt;
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let mut writer = AsyncTextWriter::new(Vec::<u8>::new());
+        let result = async {
+            writer.write_all(bytes).await?;
+            writer.shutdown().await
+        }
+        .await;
+        let inner = writer.abandon_into_inner();
+        result.map(|()| String::from_utf8(inner).unwrap())
+    })
+}
+
+#[test]
+fn test_async_write() {
+    assert_eq!(translate(b"").unwrap(), "");
+    assert_eq!(translate(b"hello\nworld\n").unwrap(), "hello\nworld\n");
+    assert_eq!(translate("A\u{30a}\n".as_bytes()).unwrap(), "\u{c5}\n");
+    translate(b"hello").unwrap_err();
+    translate(b"hello\x07\n").unwrap_err();
+    translate(b"\xff\n").unwrap_err();
+    translate("\u{301}\n".as_bytes()).unwrap_err();
+    translate(b"ab\xc3").unwrap_err();
+}
+
+#[test]
+fn test_async_write_split() {
+    use tokio::io::AsyncWriteExt;
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        // A UTF-8 sequence may be split across writes.
+        let mut writer = AsyncTextWriter::new(Vec::<u8>::new());
+        writer.write_all(b"ab\xc3").await.unwrap();
+        writer.write_all(b"\xa9\n").await.unwrap();
+        writer.shutdown().await.unwrap();
+        let inner = writer.abandon_into_inner();
+        assert_eq!(String::from_utf8(inner).unwrap(), "ab\u{e9}\n");
+
+        // Each byte may be written separately.
+        let mut writer = AsyncTextWriter::new(Vec::<u8>::new());
+        for b in "\u{1f415}x\n".bytes() {
+            writer.write_all(&[b]).await.unwrap();
+        }
+        writer.shutdown().await.unwrap();
+        let inner = writer.abandon_into_inner();
+        assert_eq!(String::from_utf8(inner).unwrap(), "\u{1f415}x\n");
+
+        // After an error, the stream can't be shut down cleanly.
+        let mut writer = AsyncTextWriter::new(Vec::<u8>::new());
+        writer.write_all(b"ab\xc3").await.unwrap();
+        writer.write_all(b"\xff\n").await.unwrap_err();
+        writer.flush().await.unwrap_err();
+        writer.shutdown().await.unwrap_err();
+        writer.write_all(b"\n").await.unwrap_err();
+    });
+}
@@ -1,8 +1,9 @@
-use crate::TextString;
+use crate::{ReadText, RepairStats, TextReader, TextString};
 use basic_text_internals::unicode::CGJ;
 use basic_text_internals::{is_basic_text, is_basic_text_end};
 use std::borrow::Cow;
 use std::io::{self, BufRead};
+use std::str;
 
 /// An extension trait for `BufRead` which adds functions for reading
 /// lines as `TextString`s.
@@ -58,7 +59,10 @@ pub trait BufReadText: BufRead {
     where
         Self: Sized,
     {
-        TextLines { buf: self }
+        TextLines {
+            buf: self,
+            line_number: 0,
+        }
     }
 
     /// Returns an iterator over the lines of this reader, similar to
@@ -68,7 +72,47 @@ pub trait BufReadText: BufRead {
     where
         Self: Sized,
     {
-        TextLinesLossy { buf: self }
+        TextLinesLossy {
+            buf: self,
+            line_number: 0,
+        }
+    }
+
+    /// Returns an iterator over the lines of this reader, similar to
+    /// [`text_lines_lossy`], but yielding each line along with counts of the
+    /// repairs performed to convert it to Basic Text.
+    ///
+    /// Each line is translated as if by a [`TextReader`], so for example a
+    /// U+D U+A (CRLF) line ending is counted as a normalized newline, and a
+    /// final line which lacks a trailing newline is counted as having one
+    /// appended.
+    ///
+    /// [`text_lines_lossy`]: BufReadText::text_lines_lossy
+    fn text_lines_audited(self) -> TextLinesAudited<Self>
+    where
+        Self: Sized,
+    {
+        TextLinesAudited {
+            buf: self,
+            started: false,
+        }
+    }
+
+    /// Returns an iterator over the lines of this reader, similar to
+    /// [`text_lines`], but yielding each line along with its line number,
+    /// starting at 1.
+    ///
+    /// Every line yielded is counted, including lines which produce errors
+    /// and a final line which lacks a trailing newline.
+    ///
+    /// [`text_lines`]: BufReadText::text_lines
+    fn numbered_text_lines(self) -> NumberedTextLines<Self>
+    where
+        Self: Sized,
+    {
+        NumberedTextLines {
+            lines: self.text_lines(),
+        }
     }
 }
 
@@ -82,6 +126,16 @@ pub trait BufReadText: BufRead {
 #[derive(Debug)]
 pub struct TextLines<B> {
     buf: B,
+    line_number: usize,
+}
+
+impl<B> TextLines<B> {
+    /// Return the line number of the most recently yielded line, starting
+    /// at 1, or 0 if no lines have been yielded yet.
+    #[inline]
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
 }
 
 impl<B: BufReadText> Iterator for TextLines<B> {
@@ -89,7 +143,11 @@ impl<B: BufReadText> Iterator for TextLines<B> {
 
     fn next(&mut self) -> Option<io::Result<TextString>> {
         let mut buf = TextString::new();
-        match self.buf.read_text_line(&mut buf) {
+        let result = self.buf.read_text_line(&mut buf);
+        if !matches!(result, Ok(0)) {
+            self.line_number += 1;
+        }
+        match result {
             Ok(0) => None,
             Ok(_n) => {
                 debug_assert!(buf.0.ends_with('\n'));
@@ -122,6 +180,16 @@ impl<B: BufReadText> Iterator for TextLines<B> {
 #[derive(Debug)]
 pub struct TextLinesLossy<B> {
     buf: B,
+    line_number: usize,
+}
+
+impl<B> TextLinesLossy<B> {
+    /// Return the line number of the most recently yielded line, starting
+    /// at 1, or 0 if no lines have been yielded yet.
+    #[inline]
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
 }
 
 impl<B: BufReadText> Iterator for TextLinesLossy<B> {
@@ -129,7 +197,11 @@ impl<B: BufReadText> Iterator for TextLinesLossy<B> {
 
     fn next(&mut self) -> Option<io::Result<TextString>> {
         let mut buf = TextString::new();
-        match self.buf.read_text_line_lossy(&mut buf) {
+        let result = self.buf.read_text_line_lossy(&mut buf);
+        if !matches!(result, Ok(0)) {
+            self.line_number += 1;
+        }
+        match result {
             Ok(0) => None,
             Ok(_n) => {
                 debug_assert!(buf.0.ends_with('\n'));
@@ -151,5 +223,98 @@ impl<B: BufReadText> Iterator for TextLinesLossy<B> {
     }
 }
 
+/// An iterator over the lines of an instance of `BufReadText`, along with
+/// the repairs performed on each.
+///
+/// This struct is generally created by calling [`text_lines_audited`] on a
+/// `BufReadText`. Please see the documentation of [`text_lines_audited`] for
+/// more details.
+///
+/// [`text_lines_audited`]: BufReadText::text_lines_audited
+#[derive(Debug)]
+pub struct TextLinesAudited<B> {
+    buf: B,
+
+    /// Whether a line has been read, after which a leading U+FEFF is
+    /// content rather than a BOM.
+    started: bool,
+}
+
+impl<B: BufReadText> Iterator for TextLinesAudited<B> {
+    type Item = io::Result<(TextString, RepairStats)>;
+
+    fn next(&mut self) -> Option<io::Result<(TextString, RepairStats)>> {
+        let mut line = Vec::new();
+        match self.buf.read_until(b'\n', &mut line) {
+            Ok(0) => None,
+            Ok(_n) => {
+                let mut reader = if std::mem::replace(&mut self.started, true) {
+                    TextReader::with_bom_as_content(line.as_slice())
+                } else {
+                    TextReader::new(line.as_slice())
+                };
+                let mut buf = TextString::new();
+
+                // Reading from a slice can't fail.
+                reader.read_to_text_string(&mut buf).unwrap();
+                let mut stats = reader.repair_stats();
+                stats.scalars_replaced += invalid_utf8_sequences(&line);
+
+                debug_assert!(buf.0.ends_with('\n'));
+                buf.0.pop();
+                debug_assert!(!buf.0.ends_with('\r'));
+
+                // We just popped the newline, so make sure we're not exposing
+                // an invalid end.
+                if let Some(c) = buf.0.chars().next_back() {
+                    if !is_basic_text_end(c) {
+                        buf.0.push(CGJ);
+                        stats.cgjs_inserted += 1;
+                    }
+                }
+
+                Some(Ok((buf, stats)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Count the invalid UTF-8 sequences in `bytes`, each of which is replaced
+/// by a U+FFFD when decoded.
+fn invalid_utf8_sequences(mut bytes: &[u8]) -> u64 {
+    let mut count = 0;
+    while let Err(error) = str::from_utf8(bytes) {
+        count += 1;
+        match error.error_len() {
+            Some(len) => bytes = &bytes[error.valid_up_to() + len..],
+            None => break,
+        }
+    }
+    count
+}
+
+/// An iterator over the numbered lines of an instance of `BufReadText`.
+///
+/// This struct is generally created by calling [`numbered_text_lines`] on a
+/// `BufReadText`. Please see the documentation of [`numbered_text_lines`]
+/// for more details.
+///
+/// [`numbered_text_lines`]: BufReadText::numbered_text_lines
+#[derive(Debug)]
+pub struct NumberedTextLines<B> {
+    lines: TextLines<B>,
+}
+
+impl<B: BufReadText> Iterator for NumberedTextLines<B> {
+    type Item = (usize, io::Result<TextString>);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, io::Result<TextString>)> {
+        let line = self.lines.next()?;
+        Some((self.lines.line_number(), line))
+    }
+}
+
 // Implement `BufReadText` for all `BufRead` implementations.
 impl<T: BufRead> BufReadText for T {}
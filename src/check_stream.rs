@@ -0,0 +1,86 @@
+//! Check whether a byte stream is valid strict Basic Text, without producing
+//! any output.
+
+use crate::TextWriter;
+use std::io::{self, Write};
+
+/// Check whether `bytes` would be accepted by a [`TextWriter`] as a complete
+/// stream, in the same way that writing it to a `TextWriter` wrapping
+/// [`io::sink`] and closing it would, and return the first error if not.
+///
+/// The returned error has the same [`io::ErrorKind`] as the error the
+/// `TextWriter` would report, and its message includes the byte offset in
+/// `bytes` at which the error was detected. Errors detected at the end of
+/// the stream, such as a missing final newline, are reported at
+/// `bytes.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::check_stream;
+///
+/// assert!(check_stream(b"hello\nworld\n").is_ok());
+/// assert!(check_stream(b"hello\x07\n").is_err());
+/// assert!(check_stream(b"hello").is_err());
+/// ```
+pub fn check_stream(bytes: &[u8]) -> io::Result<()> {
+    let mut writer = TextWriter::new(io::sink());
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        match writer.write(&bytes[pos..]) {
+            Ok(n) => pos += n,
+            Err(err) => {
+                let pos = pos + writer.output.error_offset();
+                writer
+                    .abandon_into_inner()
+                    .abandon_into_inner()
+                    .abandon_into_inner();
+                return Err(at_position(err, pos));
+            }
+        }
+    }
+
+    let close = || -> io::Result<()> {
+        writer
+            .close_into_inner()?
+            .close_into_inner()?
+            .close_into_inner()?;
+        Ok(())
+    };
+    close().map_err(|err| at_position(err, pos))
+}
+
+/// Annotate `err` with the byte offset at which it was detected.
+fn at_position(err: io::Error, pos: usize) -> io::Error {
+    io::Error::new(err.kind(), format!("{} at byte {}", err, pos))
+}
+
+#[test]
+fn test_check_stream() {
+    check_stream(b"").unwrap();
+    check_stream(b"hello\nworld\n").unwrap();
+    check_stream("\u{c5}\n".as_bytes()).unwrap();
+
+    let err = check_stream(b"hello\x07\n").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().ends_with(" at byte 5"), "{}", err);
+
+    let err = check_stream(b"ab\xffcd\n").unwrap_err();
+    assert!(err.to_string().ends_with(" at byte 2"), "{}", err);
+
+    let err = check_stream(b"hello").unwrap_err();
+    assert!(err.to_string().ends_with(" at byte 5"), "{}", err);
+
+    let err = check_stream("\u{301}\n".as_bytes()).unwrap_err();
+    assert!(err.to_string().ends_with(" at byte 0"), "{}", err);
+
+    check_stream(b"\x1b[31m\n").unwrap_err();
+
+    // Errors after input which needs normalization are reported at their
+    // own position, not at the preceding newline.
+    let err = check_stream("A\u{30a}\nhello world\x07\n".as_bytes()).unwrap_err();
+    assert!(err.to_string().ends_with(" at byte 15"), "{}", err);
+    let err = check_stream("A\u{30a}hello\x07\n".as_bytes()).unwrap_err();
+    assert!(err.to_string().ends_with(" at byte 8"), "{}", err);
+}
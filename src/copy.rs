@@ -1,7 +1,8 @@
-use crate::{ReadText, ReadTextLayered, TextSubstr, WriteText};
+use crate::{ReadText, ReadTextLayered, RepairStats, TextReader, TextSubstr, WriteText};
 use layered_io::Bufferable;
 use std::cmp::max;
 use std::io;
+use utf8_io::ReadStrLayered;
 
 /// Like [`std::io::copy`], but for streams that can operate directly on text
 /// strings, so we can avoid re-validating them as text.
@@ -58,6 +59,19 @@ pub fn copy_text_using_status<R: ReadTextLayered + ?Sized, W: WriteText + Buffer
     }
 }
 
+/// Like [`copy_text_using_status`], but also returns the counts of the
+/// repairs `reader` performed on its input.
+///
+/// The counts include any repairs performed by reads on `reader` before this
+/// call.
+pub fn copy_text_with_stats<Inner: ReadStrLayered, W: WriteText + Bufferable + ?Sized>(
+    reader: &mut TextReader<Inner>,
+    writer: &mut W,
+) -> io::Result<(u64, RepairStats)> {
+    let written = copy_text_using_status(reader, writer)?;
+    Ok((written, reader.repair_stats()))
+}
+
 #[test]
 fn test_copy_text() {
     use crate::{TextReader, TextStr, TextWriter};
@@ -97,3 +111,35 @@ fn test_copy_text_using_status() {
     let t = TextStr::from_text(s).unwrap();
     assert_eq!(t, &format!("{}\n", text));
 }
+
+#[test]
+fn test_copy_text_with_stats() {
+    use crate::{TextReader, TextWriter};
+    use std::io::Cursor;
+    use std::str;
+
+    let mut input = TextReader::new(Cursor::new(
+        b"\xef\xbb\xbfhello\r\n\x1b[mworld\x07".to_vec(),
+    ));
+    let mut output = TextWriter::new(Vec::new());
+
+    let (written, stats) = copy_text_with_stats(&mut input, &mut output).unwrap();
+
+    let ext = output.close_into_inner().unwrap();
+    let vec = ext.abandon_into_inner().abandon_into_inner().unwrap();
+    let s = str::from_utf8(&vec).unwrap();
+    assert_eq!(s, "hello\nworld\u{fffd}\n");
+    assert_eq!(written, s.len() as u64);
+    assert_eq!(
+        stats,
+        RepairStats {
+            newlines_normalized: 1,
+            escape_sequences_removed: 1,
+            scalars_replaced: 1,
+            newlines_appended: 1,
+            boms_removed: 1,
+            ..RepairStats::new()
+        }
+    );
+    assert_eq!(stats.total(), 5);
+}
@@ -0,0 +1,118 @@
+use basic_text_internals::unicode::{MAX_UTF8_SIZE, REPL};
+use basic_text_internals::BasicTextChars;
+use std::iter::Fuse;
+use std::str;
+
+/// Translate an arbitrary byte sequence into a sequence of Basic Text scalar
+/// values, in the same way that [`TextReader`] translates an input stream,
+/// but without any I/O.
+///
+/// Invalid UTF-8 sequences are replaced by U+FFFD.
+///
+/// [`TextReader`]: crate::TextReader
+///
+/// # Examples
+///
+/// ```rust
+/// let s: String = basic_text::decode_chars(b"hello\r\nworld".iter().copied()).collect();
+/// assert_eq!(s, "hello\nworld\n");
+/// ```
+pub fn decode_chars<I: IntoIterator<Item = u8>>(bytes: I) -> impl Iterator<Item = char> {
+    BasicTextChars::new(Utf8Chars::new(bytes.into_iter()))
+}
+
+/// An iterator which decodes UTF-8 from a byte iterator, replacing invalid
+/// sequences with U+FFFD.
+struct Utf8Chars<I: Iterator<Item = u8>> {
+    iter: Fuse<I>,
+    buf: [u8; MAX_UTF8_SIZE],
+    len: usize,
+}
+
+impl<I: Iterator<Item = u8>> Utf8Chars<I> {
+    #[inline]
+    fn new(iter: I) -> Self {
+        Self {
+            iter: Iterator::fuse(iter),
+            buf: [0; MAX_UTF8_SIZE],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn consume(&mut self, n: usize) {
+        self.buf.copy_within(n..self.len, 0);
+        self.len -= n;
+    }
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Utf8Chars<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        while self.len < MAX_UTF8_SIZE {
+            match self.iter.next() {
+                Some(b) => {
+                    self.buf[self.len] = b;
+                    self.len += 1;
+                }
+                None => break,
+            }
+        }
+        if self.len == 0 {
+            return None;
+        }
+
+        let (c, n) = match str::from_utf8(&self.buf[..self.len]) {
+            Ok(s) => {
+                let c = s.chars().next().unwrap();
+                (c, c.len_utf8())
+            }
+            Err(e) if e.valid_up_to() != 0 => {
+                // Safety: `valid_up_to` is the length of the valid prefix.
+                let c = unsafe { str::from_utf8_unchecked(&self.buf[..e.valid_up_to()]) }
+                    .chars()
+                    .next()
+                    .unwrap();
+                (c, c.len_utf8())
+            }
+            Err(e) => (REPL, e.error_len().unwrap_or(self.len)),
+        };
+        self.consume(n);
+        Some(c)
+    }
+}
+
+#[cfg(test)]
+fn test(bytes: &[u8], s: &str) {
+    assert_eq!(decode_chars(bytes.iter().copied()).collect::<String>(), s);
+
+    let mut reader = crate::TextReader::new(bytes);
+    let mut expected = String::new();
+    std::io::Read::read_to_string(&mut reader, &mut expected).unwrap();
+    assert_eq!(expected, s);
+}
+
+#[test]
+fn test_empty_string() {
+    test(b"", "");
+}
+
+#[test]
+fn test_controls() {
+    test(
+        b"\xef\xbb\xbfa\r\nb\rc\x0cd\x07\x1b[31me\x1b]title\x07f",
+        "a\nb\nc d\u{fffd}ef\n",
+    );
+}
+
+#[test]
+fn test_invalid_utf8() {
+    test(b"a\xffb\xe2\x98", "a\u{fffd}b\u{fffd}\n");
+    test(b"\xf0\x9f\x90\x95\xe2\x80\x8d", "🐕\u{200d}\u{34f}\n");
+}
+
+#[test]
+fn test_normalization() {
+    test(b"\xcc\x81e\xcc\x81\n", "\u{34f}\u{301}\u{e9}\n");
+}
@@ -0,0 +1,134 @@
+//! Check whether a string is a Basic Text filename which is portable across
+//! common filesystems.
+
+use crate::{TextError, TextStr};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Device names which Windows reserves in every directory, with or without
+/// an extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// The reason a string was rejected by [`check_filename`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum FilenameError {
+    /// The string isn't valid Basic Text.
+    NotBasicText(TextError),
+
+    /// The string is empty.
+    Empty,
+
+    /// The string contains a U+2F (`/`) path separator.
+    Separator,
+
+    /// The string contains a control code, such as U+A (newline) or U+9
+    /// (tab), which Basic Text permits but which filenames shouldn't
+    /// contain.
+    Control,
+
+    /// The string ends with U+20 (space) or U+2E (`.`), which Windows
+    /// silently strips. This includes `.` and `..`.
+    TrailingSpaceOrDot,
+
+    /// The string is a device name reserved by Windows, such as `CON` or
+    /// `nul.txt`.
+    ReservedName,
+}
+
+/// Check whether `s` is valid Basic Text and is usable as a filename on
+/// common filesystems, returning it as a `&TextStr` if so.
+///
+/// In addition to the Basic Text rules, this requires `s` to be non-empty,
+/// to contain no U+2F (`/`) and no control codes, to not end in U+20
+/// (space) or U+2E (`.`), and to not be a device name reserved by Windows,
+/// with or without an extension, in any case.
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::{check_filename, FilenameError};
+///
+/// assert_eq!(check_filename("notes.txt").unwrap(), "notes.txt");
+/// assert_eq!(check_filename("a/b"), Err(FilenameError::Separator));
+/// assert_eq!(check_filename("Con.log"), Err(FilenameError::ReservedName));
+/// ```
+pub fn check_filename(s: &str) -> Result<&TextStr, FilenameError> {
+    let text = TextStr::from_text(s).map_err(FilenameError::NotBasicText)?;
+
+    if s.is_empty() {
+        return Err(FilenameError::Empty);
+    }
+    if s.contains('/') {
+        return Err(FilenameError::Separator);
+    }
+    if s.contains(char::is_control) {
+        return Err(FilenameError::Control);
+    }
+    if s.ends_with([' ', '.']) {
+        return Err(FilenameError::TrailingSpaceOrDot);
+    }
+
+    // Windows ignores extensions, and spaces before them, when checking for
+    // reserved names.
+    let stem = s.split('.').next().unwrap().trim_end_matches(' ');
+    if RESERVED_NAMES
+        .iter()
+        .any(|name| stem.eq_ignore_ascii_case(name))
+    {
+        return Err(FilenameError::ReservedName);
+    }
+
+    Ok(text)
+}
+
+impl Display for FilenameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBasicText(err) => Display::fmt(err, f),
+            Self::Empty => f.write_str("filename is empty"),
+            Self::Separator => f.write_str("filename contains a path separator"),
+            Self::Control => f.write_str("filename contains a control code"),
+            Self::TrailingSpaceOrDot => f.write_str("filename ends with a space or dot"),
+            Self::ReservedName => f.write_str("filename is a reserved device name"),
+        }
+    }
+}
+
+impl Error for FilenameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NotBasicText(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_check_filename() {
+    check_filename("hello.txt").unwrap();
+    check_filename("caf\u{e9}").unwrap();
+    check_filename(".gitignore").unwrap();
+    check_filename("CONSOLE").unwrap();
+    check_filename("com10").unwrap();
+
+    assert!(matches!(
+        check_filename("\u{301}"),
+        Err(FilenameError::NotBasicText(_))
+    ));
+    assert_eq!(check_filename(""), Err(FilenameError::Empty));
+    assert_eq!(check_filename("a/b"), Err(FilenameError::Separator));
+    assert_eq!(check_filename("a\tb"), Err(FilenameError::Control));
+    assert_eq!(check_filename("a\n"), Err(FilenameError::Control));
+    assert_eq!(check_filename("a "), Err(FilenameError::TrailingSpaceOrDot));
+    assert_eq!(check_filename("a."), Err(FilenameError::TrailingSpaceOrDot));
+    assert_eq!(check_filename(".."), Err(FilenameError::TrailingSpaceOrDot));
+    assert_eq!(check_filename("NUL"), Err(FilenameError::ReservedName));
+    assert_eq!(
+        check_filename("lpt1.tar.gz"),
+        Err(FilenameError::ReservedName)
+    );
+    assert_eq!(check_filename("aux .txt"), Err(FilenameError::ReservedName));
+}
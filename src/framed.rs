@@ -0,0 +1,90 @@
+//! Reading and writing Basic Text messages with a length prefix.
+
+use crate::{TextStr, TextString};
+use std::io::{self, Read, Write};
+
+/// Write `s` to `w` as a frame consisting of its length in bytes, as a
+/// big-endian `u32`, followed by its contents.
+///
+/// This fails with [`io::ErrorKind::InvalidInput`] if `s` is longer than
+/// `u32::MAX` bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::{read_framed, write_framed, TextStr};
+///
+/// let mut buf = Vec::new();
+/// write_framed(&mut buf, TextStr::from_text("hello").unwrap()).unwrap();
+/// assert_eq!(buf, b"\0\0\0\x05hello");
+/// assert_eq!(read_framed(&mut buf.as_slice()).unwrap(), "hello");
+/// ```
+pub fn write_framed(w: &mut impl Write, s: &TextStr) -> io::Result<()> {
+    let len = u32::try_from(s.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "text is too long for a u32 length prefix",
+        )
+    })?;
+    w.write_all(&len.to_be_bytes())?;
+    w.write_all(s.as_bytes())
+}
+
+/// Read a frame written by [`write_framed`] from `r`, and check that its
+/// contents are valid Basic Text.
+///
+/// This fails with [`io::ErrorKind::UnexpectedEof`] if the stream ends
+/// before the end of the frame, and with [`io::ErrorKind::InvalidData`] if
+/// the contents aren't valid Basic Text. No repairs are performed.
+pub fn read_framed(r: &mut impl Read) -> io::Result<TextString> {
+    let mut len = [0_u8; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len);
+
+    // Don't trust the length prefix to size the buffer up front; grow it as
+    // the contents actually arrive.
+    let mut contents = Vec::new();
+    r.take(u64::from(len)).read_to_end(&mut contents)?;
+    if contents.len() as u64 != u64::from(len) {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "stream ended within a framed message",
+        ));
+    }
+
+    TextString::from_text_vec(contents)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[test]
+fn test_framed() {
+    let mut buf = Vec::new();
+    write_framed(&mut buf, TextStr::from_text("").unwrap()).unwrap();
+    write_framed(&mut buf, TextStr::from_text("caf\u{e9}\n").unwrap()).unwrap();
+    assert_eq!(buf, b"\0\0\0\0\0\0\0\x06caf\xc3\xa9\n");
+
+    let mut r = buf.as_slice();
+    assert_eq!(read_framed(&mut r).unwrap(), "");
+    assert_eq!(read_framed(&mut r).unwrap(), "caf\u{e9}\n");
+    assert_eq!(
+        read_framed(&mut r).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+
+    let truncated = b"\0\0\0\x05hel";
+    assert_eq!(
+        read_framed(&mut truncated.as_slice()).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+
+    for invalid in [
+        &b"\0\0\0\x02\xff\n"[..],
+        b"\0\0\0\x02\x07\n",
+        b"\0\0\0\x02\xcc\x81",
+    ] {
+        assert_eq!(
+            read_framed(&mut &invalid[..]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
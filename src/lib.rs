@@ -74,28 +74,77 @@
 #![cfg_attr(pattern, feature(pattern))]
 #![cfg_attr(extend_one, feature(extend_one))]
 
+#[cfg(feature = "tokio")]
+mod async_text_reader;
+#[cfg(feature = "tokio")]
+mod async_text_writer;
 mod buf_read_text;
+mod check_stream;
 mod copy;
+mod decode_chars;
+mod filename;
+mod framed;
+mod line_break_policy;
+mod line_ending;
+mod normalize;
+mod normalized_key;
 mod partial_eq;
 mod read_text;
+mod repair_stats;
+mod sanitize;
+mod tab_policy;
+#[cfg(feature = "bytes")]
+mod text_bytes;
 mod text_duplexer;
 mod text_input;
 mod text_output;
 mod text_reader;
+#[cfg(feature = "regex")]
+mod text_regex;
 mod text_string;
 mod text_substring;
 mod text_writer;
+mod write_stats;
 mod write_text;
 
+#[cfg(feature = "tokio")]
+pub use async_text_reader::AsyncTextReader;
+#[cfg(feature = "tokio")]
+pub use async_text_writer::AsyncTextWriter;
 pub use basic_text_internals::unicode::NORMALIZATION_BUFFER_SIZE;
 pub use basic_text_internals::unicode_normalization::UNICODE_VERSION;
-pub use basic_text_literals::{text, text_substr};
-pub use buf_read_text::{BufReadText, TextLines, TextLinesLossy};
-pub use copy::{copy_text, copy_text_using_status};
+pub use basic_text_internals::{
+    check_basic_text_char, first_violation, is_basic_text, is_basic_text_end, is_basic_text_start,
+    is_basic_text_substr, stream_safe_boundaries, BasicTextError, StreamSafeNfc,
+};
+pub use basic_text_literals::{text, text_concat, text_len, text_substr};
+pub use buf_read_text::{
+    BufReadText, NumberedTextLines, TextLines, TextLinesAudited, TextLinesLossy,
+};
+pub use check_stream::check_stream;
+pub use copy::{copy_text, copy_text_using_status, copy_text_with_stats};
+pub use decode_chars::decode_chars;
+pub use filename::{check_filename, FilenameError};
+pub use framed::{read_framed, write_framed};
+pub use line_break_policy::LineBreakPolicy;
+pub use line_ending::LineEnding;
+pub use normalize::{is_canonical, normalize};
+pub use normalized_key::NormalizedKey;
 pub use read_text::{default_read_exact_text_substr, ReadText, ReadTextLayered};
+pub use repair_stats::RepairStats;
+pub use sanitize::{sanitize, Repair, RepairKind};
+pub use tab_policy::TabPolicy;
+#[cfg(feature = "bytes")]
+pub use text_bytes::TextBytes;
 pub use text_duplexer::TextDuplexer;
-pub use text_reader::TextReader;
-pub use text_string::{default_read_to_text_string, FromTextError, TextError, TextStr, TextString};
+pub use text_reader::{IterReader, TextReader, Windows1252Reader};
+#[cfg(feature = "regex")]
+pub use text_regex::TextCaptures;
+pub use text_string::{
+    default_read_to_text_string, from_os_str, from_os_str_lossy, join, FromTextError, TextError,
+    TextStr, TextString, ValidateChunked,
+};
 pub use text_substring::{TextSubstr, TextSubstring};
 pub use text_writer::TextWriter;
+pub use write_stats::WriteStats;
 pub use write_text::{default_write_text_substr, WriteText};
@@ -0,0 +1,20 @@
+//! The `LineBreakPolicy` type.
+
+bitflags::bitflags! {
+    /// Which scalar values, besides U+A, U+D, and U+D U+A, a [`TextReader`]
+    /// translates into U+A. Scalar values which aren't selected are replaced
+    /// with U+20.
+    ///
+    /// [`TextReader`]: crate::TextReader
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct LineBreakPolicy: u8 {
+        /// Translate U+85 (NEL) into U+A.
+        const NEL = 1 << 0;
+
+        /// Translate U+2028 (LS) into U+A.
+        const LS = 1 << 1;
+
+        /// Translate U+2029 (PS) into U+A.
+        const PS = 1 << 2;
+    }
+}
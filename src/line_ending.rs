@@ -0,0 +1,20 @@
+//! The `LineEnding` type.
+
+/// The convention for line endings used by a [`TextReader`]'s input, before
+/// they were translated into U+A.
+///
+/// [`TextReader`]: crate::TextReader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// All lines ended with U+A (LF).
+    Lf,
+
+    /// All lines ended with U+D U+A (CRLF).
+    CrLf,
+
+    /// All lines ended with U+D (CR).
+    Cr,
+
+    /// Lines ended with more than one of the above.
+    Mixed,
+}
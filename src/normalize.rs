@@ -0,0 +1,133 @@
+//! Normalize an in-memory string to Basic Text, borrowing it if it's already
+//! valid, and test whether a string is already in canonical form.
+
+use crate::{TextReader, TextStr, TextString, TextWriter};
+use basic_text_internals::is_basic_text;
+use basic_text_internals::unicode::BOM;
+use basic_text_internals::unicode_normalization::{is_nfc_stream_safe_quick, IsNormalized};
+use layered_io::Bufferable;
+use std::borrow::Cow;
+use std::io::{self, Read};
+use utf8_io::WriteStr;
+
+/// Convert `s` to Basic Text, borrowing it if it's already valid.
+///
+/// Unlike [`TextString::from_text_lossy`], this treats `s` as a complete
+/// string rather than a fragment of a stream: a leading U+FEFF (BOM) is
+/// removed rather than translated into a U+2060 (WJ). As with
+/// `from_text_lossy`, no trailing newline is added.
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::normalize;
+/// use std::borrow::Cow;
+///
+/// assert!(matches!(normalize("hello\n"), Cow::Borrowed(_)));
+/// assert_eq!(normalize("\u{feff}A\u{30a}").as_str(), "\u{c5}");
+/// ```
+///
+/// [`TextString::from_text_lossy`]: crate::TextString::from_text_lossy
+pub fn normalize(s: &str) -> Cow<'_, TextStr> {
+    if is_nfc_stream_safe_quick(s.chars()) != IsNormalized::No && is_basic_text(s) {
+        return Cow::Borrowed(unsafe { TextStr::from_text_unchecked(s) });
+    }
+
+    // `TextReader` strips a leading BOM, which is what we want here.
+    let mut reader = TextReader::new(s.as_bytes());
+    let mut text = String::new();
+
+    // Reading from a slice can't fail.
+    reader.read_to_string(&mut text).unwrap();
+
+    // `TextReader` ensures the stream ends in a newline, but we don't want
+    // that behavior here. Strip a trailing newline if needed.
+    let v = s.strip_prefix(BOM).unwrap_or(s);
+    if !v.is_empty() && !v.ends_with(['\n', '\r']) {
+        let c = text.pop();
+        assert_eq!(c.unwrap(), '\n');
+    }
+
+    Cow::Owned(unsafe { TextString::from_text_unchecked(text) })
+}
+
+/// Test whether `s` is already in canonical form, meaning that writing it
+/// through a [`TextWriter`] would produce byte-identical output.
+///
+/// This differs from [`is_basic_text`], which checks whether a string is
+/// structurally valid; this checks whether it's unchanged by the stream
+/// transform, which also requires it to be in Stream-Safe NFC.
+///
+/// A string which doesn't end in a newline is treated as if it were written
+/// with [`TextWriter::finish_without_newline`].
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::is_canonical;
+///
+/// assert!(is_canonical("hello\n"));
+/// assert!(is_canonical("\u{c5}\n"));
+/// assert!(!is_canonical("A\u{30a}\n"));
+/// assert!(!is_canonical("hello\r\n"));
+/// ```
+///
+/// [`is_basic_text`]: crate::is_basic_text
+pub fn is_canonical(s: &str) -> bool {
+    fn write(s: &str) -> io::Result<Vec<u8>> {
+        let mut writer = TextWriter::new(Vec::new());
+        if let Err(err) = writer.write_str(s) {
+            writer.abandon();
+            return Err(err);
+        }
+        let inner = if s.is_empty() || s.ends_with('\n') {
+            writer.close_into_inner()?
+        } else {
+            writer.finish_without_newline()?
+        };
+        inner.close_into_inner()?.close_into_inner()
+    }
+
+    match write(s) {
+        Ok(output) => output == s.as_bytes(),
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn test_normalize() {
+    assert!(matches!(normalize(""), Cow::Borrowed(_)));
+    assert!(matches!(normalize("hello"), Cow::Borrowed(_)));
+    assert!(matches!(normalize("hello\nworld\n"), Cow::Borrowed(_)));
+
+    assert_eq!(normalize("A\u{30a}").as_str(), "\u{c5}");
+    assert_eq!(normalize("A\u{30a}\n").as_str(), "\u{c5}\n");
+    assert_eq!(normalize("\u{feff}").as_str(), "");
+    assert_eq!(normalize("\u{feff}hello").as_str(), "hello");
+    assert_eq!(
+        normalize("hello\u{feff}world").as_str(),
+        "hello\u{2060}world"
+    );
+    assert_eq!(normalize("\u{301}x").as_str(), "\u{34f}\u{301}x");
+    assert_eq!(normalize("x\u{200d}").as_str(), "x\u{200d}\u{34f}");
+    assert_eq!(normalize("a\r\nb\r").as_str(), "a\nb\n");
+    assert_eq!(normalize("a\u{7}").as_str(), "a\u{fffd}");
+}
+
+#[test]
+fn test_is_canonical() {
+    assert!(is_canonical(""));
+    assert!(is_canonical("\n"));
+    assert!(is_canonical("hello"));
+    assert!(is_canonical("hello\nworld\n"));
+    assert!(is_canonical("\u{c5}\n"));
+    assert!(is_canonical("\u{34f}\u{301}x\n"));
+
+    assert!(!is_canonical("A\u{30a}\n"));
+    assert!(!is_canonical("\u{212b}\n"));
+    assert!(!is_canonical("\u{feff}hello\n"));
+    assert!(!is_canonical("hello\r\n"));
+    assert!(!is_canonical("hello\u{7}\n"));
+    assert!(!is_canonical("\u{301}x\n"));
+    assert!(!is_canonical("x\u{200d}"));
+}
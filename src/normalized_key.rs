@@ -0,0 +1,97 @@
+//! The `NormalizedKey` type.
+
+use crate::{TextStr, TextString};
+use std::borrow::Borrow;
+use std::ops::Deref;
+
+/// A key for maps and sets of text which may have arrived in different
+/// forms before being translated into Basic Text.
+///
+/// Strings which differ only in ways that the Basic Text translation
+/// removes, such as "\u{c5}" and "A\u{30a}", produce equal keys with equal
+/// hashes. `NormalizedKey` implements `Borrow<TextStr>`, and its `Hash` is
+/// consistent with [`TextStr::normalized_hash`].
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::NormalizedKey;
+/// use std::collections::HashSet;
+///
+/// let mut set = HashSet::new();
+/// set.insert(NormalizedKey::from_text_lossy("\u{c5}"));
+/// assert!(!set.insert(NormalizedKey::from_text_lossy("A\u{30a}")));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct NormalizedKey(pub TextString);
+
+impl NormalizedKey {
+    /// Construct a new instance of `NormalizedKey` by translating `s` into
+    /// Basic Text, in the same way as [`TextString::from_text_lossy`].
+    #[inline]
+    pub fn from_text_lossy(s: &str) -> Self {
+        Self(TextString::from_text_lossy(s).into_owned())
+    }
+
+    /// Return the underlying `TextString`.
+    #[inline]
+    pub fn into_inner(self) -> TextString {
+        self.0
+    }
+}
+
+impl From<TextString> for NormalizedKey {
+    #[inline]
+    fn from(s: TextString) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&TextStr> for NormalizedKey {
+    #[inline]
+    fn from(s: &TextStr) -> Self {
+        Self(s.to_owned())
+    }
+}
+
+impl Deref for NormalizedKey {
+    type Target = TextStr;
+
+    #[inline]
+    fn deref(&self) -> &TextStr {
+        &self.0
+    }
+}
+
+impl Borrow<TextStr> for NormalizedKey {
+    #[inline]
+    fn borrow(&self) -> &TextStr {
+        &self.0
+    }
+}
+
+#[test]
+fn normalized_key() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        t.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let composed = NormalizedKey::from_text_lossy("\u{c5}");
+    let decomposed = NormalizedKey::from_text_lossy("A\u{30a}");
+    assert_eq!(composed, decomposed);
+    assert_eq!(hash(&composed), hash(&decomposed));
+    assert_ne!(composed, NormalizedKey::from_text_lossy("A"));
+
+    // `Hash` is consistent with `Borrow<TextStr>`.
+    let text = TextStr::from_text("\u{c5}").unwrap();
+    assert_eq!(hash(&composed), hash(text));
+
+    let mut hasher = DefaultHasher::new();
+    text.normalized_hash(&mut hasher);
+    assert_eq!(hash(&composed), hasher.finish());
+}
@@ -201,6 +201,34 @@ impl PartialEq<TextStr> for String {
     }
 }
 
+impl<'a> PartialEq<&'a str> for TextStr {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.0.eq(*other)
+    }
+}
+
+impl PartialEq<TextStr> for &str {
+    #[inline]
+    fn eq(&self, other: &TextStr) -> bool {
+        (*self).eq(&other.0)
+    }
+}
+
+impl PartialEq<str> for &TextStr {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq(other)
+    }
+}
+
+impl<'a> PartialEq<&'a TextStr> for str {
+    #[inline]
+    fn eq(&self, other: &&'a TextStr) -> bool {
+        self.eq(&other.0)
+    }
+}
+
 impl PartialEq<TextSubstr> for str {
     #[inline]
     fn eq(&self, other: &TextSubstr) -> bool {
@@ -397,6 +425,34 @@ impl PartialEq<TextSubstr> for String {
     }
 }
 
+impl<'a> PartialEq<&'a str> for TextSubstr {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.0.eq(*other)
+    }
+}
+
+impl PartialEq<TextSubstr> for &str {
+    #[inline]
+    fn eq(&self, other: &TextSubstr) -> bool {
+        (*self).eq(&other.0)
+    }
+}
+
+impl PartialEq<str> for &TextSubstr {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq(other)
+    }
+}
+
+impl<'a> PartialEq<&'a TextSubstr> for str {
+    #[inline]
+    fn eq(&self, other: &&'a TextSubstr) -> bool {
+        self.eq(&other.0)
+    }
+}
+
 impl<'a> PartialEq<&'a TextStr> for TextSubstring {
     #[inline]
     fn eq(&self, other: &&'a TextStr) -> bool {
@@ -522,3 +578,25 @@ impl<'a, 'b> PartialEq<Cow<'a, TextSubstr>> for &'b TextStr {
         self.0.eq(&other.0)
     }
 }
+
+#[test]
+fn test_str_comparisons() {
+    let text_str = TextStr::from_text("hello").unwrap();
+    assert!(text_str == "hello");
+    assert!("hello" == text_str);
+    assert!(*text_str == "hello");
+    assert!(*"hello" == text_str);
+    let string = String::from("hello");
+    assert!(text_str == string);
+
+    let text_string = text_str.to_owned();
+    assert!(text_string == string);
+    assert!(text_string == "hello");
+    assert!(text_string == *text_str);
+
+    let text_substr = TextSubstr::from_text("\u{301}hello").unwrap();
+    assert!(text_substr == "\u{301}hello");
+    assert!("\u{301}hello" == text_substr);
+    assert!(*text_substr == "\u{301}hello");
+    assert!(*"\u{301}hello" == text_substr);
+}
@@ -0,0 +1,87 @@
+//! Counters describing the repairs performed while converting an arbitrary
+//! input stream into Basic Text.
+
+use crate::RepairKind;
+
+/// Counts of the repairs a [`TextReader`] performed while translating its
+/// input into Basic Text.
+///
+/// Normalization to Stream-Safe NFC is not counted, as it doesn't change the
+/// meaning of the text.
+///
+/// [`TextReader`]: crate::TextReader
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RepairStats {
+    /// The number of U+D (CR), U+D U+A (CRLF), and, in the corresponding
+    /// compatibility modes, U+85 (NEL), U+2028 (LS), and U+2029 (PS) newlines
+    /// translated into U+A.
+    pub newlines_normalized: u64,
+
+    /// The number of U+C (FF) sequences replaced by U+20 or removed.
+    pub form_feeds_replaced: u64,
+
+    /// The number of escape sequences removed.
+    pub escape_sequences_removed: u64,
+
+    /// The number of scalar values replaced, such as disallowed control
+    /// codes replaced by U+FFFD or ligatures expanded.
+    pub scalars_replaced: u64,
+
+    /// The number of U+34F (CGJ)s inserted to guard non-starters and
+    /// non-ending scalar values.
+    pub cgjs_inserted: u64,
+
+    /// The number of U+A newlines appended at the end of a stream.
+    pub newlines_appended: u64,
+
+    /// The number of leading U+FEFF (BOM)s removed.
+    pub boms_removed: u64,
+}
+
+impl RepairStats {
+    /// Construct a new instance of `RepairStats` with all counts zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            newlines_normalized: 0,
+            form_feeds_replaced: 0,
+            escape_sequences_removed: 0,
+            scalars_replaced: 0,
+            cgjs_inserted: 0,
+            newlines_appended: 0,
+            boms_removed: 0,
+        }
+    }
+
+    /// Return the total number of repairs.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.newlines_normalized
+            + self.form_feeds_replaced
+            + self.escape_sequences_removed
+            + self.scalars_replaced
+            + self.cgjs_inserted
+            + self.newlines_appended
+            + self.boms_removed
+    }
+
+    /// Test whether no repairs were performed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Increment the count for `kind`.
+    #[inline]
+    pub(crate) fn count(&mut self, kind: RepairKind) {
+        match kind {
+            RepairKind::NewlineNormalized => self.newlines_normalized += 1,
+            RepairKind::FormFeedReplaced => self.form_feeds_replaced += 1,
+            RepairKind::EscapeSequenceRemoved => self.escape_sequences_removed += 1,
+            RepairKind::ScalarReplaced => self.scalars_replaced += 1,
+            RepairKind::CgjInserted => self.cgjs_inserted += 1,
+            RepairKind::NewlineAppended => self.newlines_appended += 1,
+            RepairKind::BomRemoved => self.boms_removed += 1,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Translate a string into Basic Text, describing each repair performed.
+
+use crate::{ReadText, TextReader, TextString};
+use std::ops::Range;
+
+/// The kind of a [`Repair`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepairKind {
+    /// A U+D (CR), U+D U+A (CRLF), or, in the corresponding compatibility
+    /// modes, U+85 (NEL), U+2028 (LS), or U+2029 (PS), was translated into
+    /// U+A.
+    NewlineNormalized,
+
+    /// A sequence of U+C (FF) was replaced by U+20, or removed before a
+    /// newline.
+    FormFeedReplaced,
+
+    /// An escape sequence was removed.
+    EscapeSequenceRemoved,
+
+    /// A scalar value was replaced, such as a disallowed control code
+    /// replaced by U+FFFD or a ligature expanded.
+    ScalarReplaced,
+
+    /// A U+34F (CGJ) was inserted to guard a non-starter or a non-ending
+    /// scalar value.
+    CgjInserted,
+
+    /// A U+A newline was appended at the end of the input.
+    NewlineAppended,
+
+    /// A leading U+FEFF (BOM) was removed.
+    BomRemoved,
+}
+
+/// A description of a single change made while translating input into
+/// Basic Text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Repair {
+    /// The range of bytes in the input which were changed. For insertions,
+    /// this is an empty range at the insertion point.
+    pub byte_range: Range<usize>,
+
+    /// What kind of change was made.
+    pub kind: RepairKind,
+
+    /// The text which replaced the input bytes, before normalization.
+    pub replacement: String,
+}
+
+/// Translate `input` into Basic Text, in the same way that [`TextReader`]
+/// does, and return the result along with a list of the repairs performed,
+/// in input order.
+///
+/// Normalization to Stream-Safe NFC is not reported as a repair, as it
+/// doesn't change the meaning of the text.
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::{sanitize, RepairKind};
+///
+/// let (text, repairs) = sanitize("hello\r\nworld\u{7}");
+/// assert_eq!(text, "hello\nworld\u{fffd}\n");
+/// assert_eq!(repairs.len(), 3);
+/// assert_eq!(repairs[0].kind, RepairKind::NewlineNormalized);
+/// assert_eq!(repairs[0].byte_range, 5..7);
+/// assert_eq!(repairs[1].kind, RepairKind::ScalarReplaced);
+/// assert_eq!(repairs[1].byte_range, 12..13);
+/// assert_eq!(repairs[2].kind, RepairKind::NewlineAppended);
+/// assert_eq!(repairs[2].byte_range, 13..13);
+/// ```
+pub fn sanitize(input: &str) -> (TextString, Vec<Repair>) {
+    let mut reader = TextReader::new(input.as_bytes());
+    reader.input.repairs = Some(Vec::new());
+
+    let mut text = TextString::new();
+
+    // Reading from a slice can't fail.
+    reader.read_to_text_string(&mut text).unwrap();
+
+    (text, reader.input.repairs.take().unwrap())
+}
+
+#[cfg(test)]
+fn repair(byte_range: Range<usize>, kind: RepairKind, replacement: &str) -> Repair {
+    Repair {
+        byte_range,
+        kind,
+        replacement: replacement.to_owned(),
+    }
+}
+
+#[test]
+fn test_sanitize_clean() {
+    let (text, repairs) = sanitize("hello world\n");
+    assert_eq!(text, "hello world\n");
+    assert!(repairs.is_empty());
+
+    let (text, repairs) = sanitize("");
+    assert_eq!(text, "");
+    assert!(repairs.is_empty());
+}
+
+#[test]
+fn test_sanitize_newlines() {
+    let (text, repairs) = sanitize("a\r\nb\rc");
+    assert_eq!(text, "a\nb\nc\n");
+    assert_eq!(
+        repairs,
+        vec![
+            repair(1..3, RepairKind::NewlineNormalized, "\n"),
+            repair(4..5, RepairKind::NewlineNormalized, "\n"),
+            repair(6..6, RepairKind::NewlineAppended, "\n"),
+        ]
+    );
+}
+
+#[test]
+fn test_sanitize_form_feeds() {
+    let (text, repairs) = sanitize("a\x0c\x0cb\x0c\n");
+    assert_eq!(text, "a b\n");
+    assert_eq!(
+        repairs,
+        vec![
+            repair(1..3, RepairKind::FormFeedReplaced, " "),
+            repair(4..5, RepairKind::FormFeedReplaced, ""),
+        ]
+    );
+}
+
+#[test]
+fn test_sanitize_escapes() {
+    let (text, repairs) = sanitize("\x1b[31mred\x1b]0;title\x07\x1b\n");
+    assert_eq!(text, "red\n");
+    assert_eq!(
+        repairs,
+        vec![
+            repair(0..5, RepairKind::EscapeSequenceRemoved, ""),
+            repair(8..18, RepairKind::EscapeSequenceRemoved, ""),
+            repair(18..19, RepairKind::EscapeSequenceRemoved, ""),
+        ]
+    );
+}
+
+#[test]
+fn test_sanitize_replacements() {
+    let (text, repairs) = sanitize("\u{feff}\u{301}x\u{fb01}\u{200d}");
+    assert_eq!(text, "\u{34f}\u{301}xfi\u{200d}\u{34f}\n");
+    assert_eq!(
+        repairs,
+        vec![
+            repair(0..3, RepairKind::BomRemoved, ""),
+            repair(3..3, RepairKind::CgjInserted, "\u{34f}"),
+            repair(6..9, RepairKind::ScalarReplaced, "fi"),
+            repair(12..12, RepairKind::CgjInserted, "\u{34f}"),
+            repair(12..12, RepairKind::NewlineAppended, "\n"),
+        ]
+    );
+}
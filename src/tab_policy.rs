@@ -0,0 +1,24 @@
+//! The `TabPolicy` type.
+
+/// What a [`TextReader`] does with U+9 (tab).
+///
+/// [`TextReader`]: crate::TextReader
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TabPolicy {
+    /// Pass U+9 through unchanged. Basic Text permits U+9, so this is the
+    /// default.
+    #[default]
+    Preserve,
+
+    /// Replace each U+9 with enough U+20s to advance to the next multiple of
+    /// the given number of columns. Columns are counted from the start of
+    /// the line, using the display width of each scalar value. The width
+    /// must be nonzero.
+    ExpandTo(usize),
+
+    /// Fail with an error of kind [`io::ErrorKind::InvalidData`] if the
+    /// input contains a U+9.
+    ///
+    /// [`io::ErrorKind::InvalidData`]: std::io::ErrorKind::InvalidData
+    Reject,
+}
@@ -0,0 +1,124 @@
+//! Interoperability with the `bytes` crate's `Bytes` buffers.
+
+use crate::{FromTextError, TextError, TextStr, TextString};
+use bytes::Bytes;
+use std::borrow::Borrow;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Deref;
+
+impl TextString {
+    /// Converts a `Bytes` buffer to a `TextString`.
+    ///
+    /// This behaves like [`from_text_vec`]. If `b` is the only handle to its
+    /// buffer, the buffer is converted into a `Vec` without copying.
+    ///
+    /// [`from_text_vec`]: Self::from_text_vec
+    #[inline]
+    pub fn from_text_bytes_buf(b: Bytes) -> Result<Self, FromTextError> {
+        Self::from_text_vec(Vec::from(b))
+    }
+}
+
+/// A `Bytes` buffer which is known to contain valid Basic Text, which
+/// dereferences to a [`TextStr`] view of its contents.
+///
+/// Cloning a `TextBytes` is cheap, as it shares the underlying buffer.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct TextBytes(Bytes);
+
+impl TextBytes {
+    /// Converts a `Bytes` buffer to a `TextBytes`, if it contains valid
+    /// Basic Text, without copying it.
+    #[inline]
+    pub fn from_text_bytes(b: Bytes) -> Result<Self, TextError> {
+        TextStr::from_text_bytes(&b)?;
+        Ok(Self(b))
+    }
+
+    /// Returns a text string slice of the contents of this `TextBytes`.
+    #[inline]
+    pub fn as_text_str(&self) -> &TextStr {
+        // Safety: The contents were validated when `self` was created.
+        unsafe { TextStr::from_text_bytes_unchecked(&self.0) }
+    }
+
+    /// Returns the underlying `Bytes` buffer.
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+
+impl Deref for TextBytes {
+    type Target = TextStr;
+
+    #[inline]
+    fn deref(&self) -> &TextStr {
+        self.as_text_str()
+    }
+}
+
+impl AsRef<TextStr> for TextBytes {
+    #[inline]
+    fn as_ref(&self) -> &TextStr {
+        self.as_text_str()
+    }
+}
+
+impl AsRef<[u8]> for TextBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Borrow<TextStr> for TextBytes {
+    #[inline]
+    fn borrow(&self) -> &TextStr {
+        self.as_text_str()
+    }
+}
+
+impl From<TextString> for TextBytes {
+    #[inline]
+    fn from(s: TextString) -> Self {
+        Self(Bytes::from(s.into_string()))
+    }
+}
+
+impl From<TextBytes> for Bytes {
+    #[inline]
+    fn from(b: TextBytes) -> Self {
+        b.0
+    }
+}
+
+impl Display for TextBytes {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_text_str(), f)
+    }
+}
+
+#[test]
+fn text_bytes() {
+    let b = Bytes::from_static(b"hello\n");
+    let t = TextBytes::from_text_bytes(b.clone()).unwrap();
+    assert_eq!(t.as_text_str(), "hello\n");
+    assert_eq!(t.as_bytes().as_ptr(), b.as_ptr());
+    assert_eq!(t.to_string(), "hello\n");
+    assert_eq!(t.into_bytes(), b);
+
+    TextBytes::from_text_bytes(Bytes::from_static(b"\xff\n")).unwrap_err();
+    TextBytes::from_text_bytes(Bytes::from_static("\u{301}".as_bytes())).unwrap_err();
+
+    let t = TextBytes::from(TextString::from_text("world\n".to_owned()).unwrap());
+    assert_eq!(&*t, "world\n");
+}
+
+#[test]
+fn from_text_bytes_buf() {
+    let s = TextString::from_text_bytes_buf(Bytes::from_static(b"hello\n")).unwrap();
+    assert_eq!(s, "hello\n");
+    TextString::from_text_bytes_buf(Bytes::from_static(b"\x07\n")).unwrap_err();
+}
@@ -1,6 +1,9 @@
 use crate::text_input::TextInput;
 use crate::text_output::TextOutput;
-use crate::{ReadText, ReadTextLayered, TextSubstr, WriteText};
+use crate::{
+    LineBreakPolicy, ReadText, ReadTextLayered, RepairStats, TextStr, TextString, TextSubstr,
+    WriteText,
+};
 use duplex::{Duplex, HalfDuplex};
 #[cfg(windows)]
 use io_extras::os::windows::{
@@ -78,6 +81,43 @@ impl<Inner: HalfDuplex> TextDuplexer<Utf8Duplexer<LayeredDuplexer<Inner>>> {
     pub fn with_lsps_compatibility(inner: Inner) -> io::Result<Self> {
         Self::from_utf8_with_lsps_compatibility(Utf8Duplexer::new(LayeredDuplexer::new(inner)))
     }
+
+    /// Like `new`, but preserves U+C (FF) page breaks on lines by themselves
+    /// on input, and permits them on output.
+    ///
+    /// Since U+C isn't Basic Text, the input in this mode may only be read
+    /// as bytes or as a `str`; the `ReadText` methods fail with an error.
+    #[inline]
+    pub fn with_form_feed_compatibility(inner: Inner) -> io::Result<Self> {
+        Self::from_utf8_with_form_feed_compatibility(Utf8Duplexer::new(LayeredDuplexer::new(inner)))
+    }
+
+    /// Like `new`, but permits the control codes in `allowed_controls` to
+    /// pass through verbatim on output. All other control codes are still
+    /// diagnosed as errors.
+    #[inline]
+    pub fn with_allowed_controls(inner: Inner, allowed_controls: &[char]) -> Self {
+        Self::from_utf8_with_allowed_controls(
+            Utf8Duplexer::new(LayeredDuplexer::new(inner)),
+            allowed_controls,
+        )
+    }
+
+    /// Like `new`, but treats a leading U+FEFF (BOM) on input as content,
+    /// replacing it with U+2060 (WJ) like any other U+FEFF, instead of
+    /// removing it.
+    #[inline]
+    pub fn with_bom_as_content(inner: Inner) -> Self {
+        Self::from_utf8_with_bom_as_content(Utf8Duplexer::new(LayeredDuplexer::new(inner)))
+    }
+
+    /// Like `new`, but fails with an error if more than `limit` bytes of
+    /// input are read without a line boundary, rather than buffering them
+    /// without bound.
+    #[inline]
+    pub fn with_max_buffer(inner: Inner, limit: usize) -> Self {
+        Self::from_utf8_with_max_buffer(Utf8Duplexer::new(LayeredDuplexer::new(inner)), limit)
+    }
 }
 
 impl<Inner: HalfDuplex + ReadStr + ReadLayered + ReadStrLayered + WriteStr + WriteLayered>
@@ -128,7 +168,7 @@ impl<Inner: HalfDuplex + ReadStr + ReadLayered + ReadStrLayered + WriteStr + Wri
     /// Like `from_utf8`, but replaces U+85 (NEL) with U+A instead of U+20.
     #[inline]
     pub fn from_utf8_with_nel_compatibility(inner: Inner) -> io::Result<Self> {
-        let input = TextInput::with_nel_compatibility();
+        let input = TextInput::with_line_break_policy(LineBreakPolicy::NEL);
         let output = TextOutput::new();
         Ok(Self {
             inner,
@@ -141,7 +181,7 @@ impl<Inner: HalfDuplex + ReadStr + ReadLayered + ReadStrLayered + WriteStr + Wri
     /// instead of U+20.
     #[inline]
     pub fn from_utf8_with_lsps_compatibility(inner: Inner) -> io::Result<Self> {
-        let input = TextInput::with_lsps_compatibility();
+        let input = TextInput::with_line_break_policy(LineBreakPolicy::LS | LineBreakPolicy::PS);
         let output = TextOutput::new();
         Ok(Self {
             inner,
@@ -150,6 +190,64 @@ impl<Inner: HalfDuplex + ReadStr + ReadLayered + ReadStrLayered + WriteStr + Wri
         })
     }
 
+    /// Like `from_utf8`, but preserves U+C (FF) page breaks on lines by
+    /// themselves on input, and permits them on output.
+    ///
+    /// Since U+C isn't Basic Text, the input in this mode may only be read
+    /// as bytes or as a `str`; the `ReadText` methods fail with an error.
+    #[inline]
+    pub fn from_utf8_with_form_feed_compatibility(inner: Inner) -> io::Result<Self> {
+        let input = TextInput::with_form_feed_compatibility();
+        let output = TextOutput::with_form_feed_compatibility();
+        Ok(Self {
+            inner,
+            input,
+            output,
+        })
+    }
+
+    /// Like `from_utf8`, but permits the control codes in `allowed_controls`
+    /// to pass through verbatim on output. All other control codes are still
+    /// diagnosed as errors.
+    #[inline]
+    pub fn from_utf8_with_allowed_controls(inner: Inner, allowed_controls: &[char]) -> Self {
+        Self {
+            inner,
+            input: TextInput::new(),
+            output: TextOutput::with_allowed_controls(allowed_controls),
+        }
+    }
+
+    /// Like `from_utf8`, but treats a leading U+FEFF (BOM) on input as
+    /// content, replacing it with U+2060 (WJ) like any other U+FEFF, instead
+    /// of removing it.
+    #[inline]
+    pub fn from_utf8_with_bom_as_content(inner: Inner) -> Self {
+        Self {
+            inner,
+            input: TextInput::with_bom_as_content(),
+            output: TextOutput::new(),
+        }
+    }
+
+    /// Like `from_utf8`, but fails with an error if more than `limit` bytes
+    /// of input are read without a line boundary, rather than buffering them
+    /// without bound.
+    #[inline]
+    pub fn from_utf8_with_max_buffer(inner: Inner, limit: usize) -> Self {
+        Self {
+            inner,
+            input: TextInput::with_max_buffer(limit),
+            output: TextOutput::new(),
+        }
+    }
+
+    /// Return the counts of the repairs performed on the input so far.
+    #[inline]
+    pub fn repair_stats(&self) -> RepairStats {
+        self.input.stats
+    }
+
     /// Flush and close the underlying stream and return the underlying
     /// stream object.
     #[inline]
@@ -162,6 +260,23 @@ impl<Inner: HalfDuplex + ReadStr + ReadLayered + ReadStrLayered + WriteStr + Wri
     pub fn abandon_into_inner(self) -> Inner {
         TextOutput::abandon_into_inner(self)
     }
+
+    /// Return a reference to the underlying stream object.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the underlying stream object.
+    ///
+    /// Input and output may be buffered in this `TextDuplexer`. Reading from
+    /// or writing to the underlying stream directly bypasses the translation
+    /// state of this `TextDuplexer`, so subsequent input may be translated
+    /// differently, and the output may not be valid Basic Text.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
 }
 
 #[cfg(feature = "terminal-io")]
@@ -312,6 +427,11 @@ impl<Inner: HalfDuplexLayered + ReadStrLayered + WriteStr> ReadText for TextDupl
 
         Ok(())
     }
+
+    #[inline]
+    fn read_to_text_string(&mut self, buf: &mut TextString) -> io::Result<usize> {
+        TextInput::read_to_text_string(self, buf)
+    }
 }
 
 impl<Inner: HalfDuplexLayered + ReadStrLayered + WriteStr> ReadTextLayered for TextDuplexer<Inner> {
@@ -361,6 +481,11 @@ impl<Inner: HalfDuplexLayered + ReadStrLayered + WriteStr> WriteStr for TextDupl
 }
 
 impl<Inner: HalfDuplexLayered + ReadStrLayered + WriteStr> WriteText for TextDuplexer<Inner> {
+    #[inline]
+    fn write_text(&mut self, s: &TextStr) -> io::Result<()> {
+        TextOutput::write_text(self, s)
+    }
+
     #[inline]
     fn write_text_substr(&mut self, s: &TextSubstr) -> io::Result<()> {
         TextOutput::write_text_substr(self, s)
@@ -441,3 +566,151 @@ impl<Inner: HalfDuplexLayered + ReadStr + WriteStr + Debug> Debug for TextDuplex
         b.finish()
     }
 }
+
+/// An in-memory `HalfDuplex` for testing, reading from `input` and writing
+/// to `output`, which is shared so that it outlives the stream, which is
+/// dropped when the input ends.
+#[cfg(test)]
+struct TestDuplex {
+    input: &'static [u8],
+    output: std::rc::Rc<std::cell::RefCell<Vec<u8>>>,
+}
+
+#[cfg(test)]
+impl Read for TestDuplex {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+#[cfg(test)]
+impl Write for TestDuplex {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Duplex for TestDuplex {}
+
+#[cfg(test)]
+type TestTextDuplexer = TextDuplexer<Utf8Duplexer<LayeredDuplexer<TestDuplex>>>;
+
+#[cfg(test)]
+fn utf8(inner: TestDuplex) -> Utf8Duplexer<LayeredDuplexer<TestDuplex>> {
+    Utf8Duplexer::new(LayeredDuplexer::new(inner))
+}
+
+/// Construct a `TextDuplexer` over `input` with `make`, write `output` to
+/// it, and then read all of its input, returning what was read and what was
+/// written to the underlying stream.
+#[cfg(test)]
+fn round_trip(
+    make: impl FnOnce(TestDuplex) -> io::Result<TestTextDuplexer>,
+    input: &'static [u8],
+    output: &[u8],
+) -> io::Result<(String, String)> {
+    let written = std::rc::Rc::default();
+    let mut duplexer = make(TestDuplex {
+        input,
+        output: std::rc::Rc::clone(&written),
+    })?;
+    let mut s = String::new();
+    match duplexer
+        .write_all(output)
+        .and_then(|()| duplexer.flush())
+        .and_then(|()| duplexer.read_to_string(&mut s))
+    {
+        Ok(_) => (),
+        Err(err) => {
+            duplexer.abandon();
+            return Err(err);
+        }
+    }
+    let written = String::from_utf8(written.take()).unwrap();
+    Ok((s, written))
+}
+
+#[test]
+fn test_form_feed_compatibility() {
+    // The input contains U+C, so it can only be read with the `Read` methods.
+    let expected = ("a\n\x0c\nb\n".to_owned(), "c\n\x0c\nd\n".to_owned());
+    let make = TextDuplexer::with_form_feed_compatibility;
+    assert_eq!(
+        round_trip(make, b"a\x0cb", b"c\n\x0c\nd\n").unwrap(),
+        expected
+    );
+    let make = |inner| TextDuplexer::from_utf8_with_form_feed_compatibility(utf8(inner));
+    assert_eq!(
+        round_trip(make, b"a\x0cb", b"c\n\x0c\nd\n").unwrap(),
+        expected
+    );
+
+    let make = |inner| Ok(TextDuplexer::new(inner));
+    assert_eq!(
+        round_trip(make, b"a\x0cb", b"c\n").unwrap(),
+        ("a b\n".to_owned(), "c\n".to_owned())
+    );
+    round_trip(make, b"", b"c\n\x0c\nd\n").unwrap_err();
+}
+
+#[test]
+fn test_allowed_controls() {
+    // Allowed controls are only permitted on output.
+    let expected = ("\u{fffd}a\n".to_owned(), "\x07b\n".to_owned());
+    let make = |inner| Ok(TextDuplexer::with_allowed_controls(inner, &['\x07']));
+    assert_eq!(round_trip(make, b"\x07a\n", b"\x07b\n").unwrap(), expected);
+    round_trip(make, b"", b"\x08b\n").unwrap_err();
+    let make = |inner| {
+        Ok(TextDuplexer::from_utf8_with_allowed_controls(
+            utf8(inner),
+            &['\x07'],
+        ))
+    };
+    assert_eq!(round_trip(make, b"\x07a\n", b"\x07b\n").unwrap(), expected);
+
+    let make = |inner| Ok(TextDuplexer::new(inner));
+    round_trip(make, b"", b"\x07b\n").unwrap_err();
+}
+
+#[test]
+fn test_bom_as_content() {
+    let input = "\u{feff}a\n".as_bytes();
+    let expected = ("\u{2060}a\n".to_owned(), "b\n".to_owned());
+    let make = |inner| Ok(TextDuplexer::with_bom_as_content(inner));
+    assert_eq!(round_trip(make, input, b"b\n").unwrap(), expected);
+    let make = |inner| Ok(TextDuplexer::from_utf8_with_bom_as_content(utf8(inner)));
+    assert_eq!(round_trip(make, input, b"b\n").unwrap(), expected);
+
+    let make = |inner| Ok(TextDuplexer::new(inner));
+    assert_eq!(
+        round_trip(make, input, b"b\n").unwrap(),
+        ("a\n".to_owned(), "b\n".to_owned())
+    );
+}
+
+#[test]
+fn test_max_buffer() {
+    let long = "x".repeat(10000).leak().as_bytes();
+
+    let expected = ("hello\nworld\n".to_owned(), "c\n".to_owned());
+    let make = |inner| Ok(TextDuplexer::with_max_buffer(inner, 8));
+    assert_eq!(
+        round_trip(make, b"hello\nworld\n", b"c\n").unwrap(),
+        expected
+    );
+    let make = |inner| Ok(TextDuplexer::with_max_buffer(inner, 8192));
+    round_trip(make, long, b"c\n").unwrap_err();
+
+    let make = |inner| Ok(TextDuplexer::from_utf8_with_max_buffer(utf8(inner), 8));
+    assert_eq!(
+        round_trip(make, b"hello\nworld\n", b"c\n").unwrap(),
+        expected
+    );
+    let make = |inner| Ok(TextDuplexer::from_utf8_with_max_buffer(utf8(inner), 8192));
+    round_trip(make, long, b"c\n").unwrap_err();
+}
@@ -1,23 +1,32 @@
 //! Shared implementation for `TextReader` and the reader half of
 //! `TextDuplexer`.
 
-use crate::{TextDuplexer, TextReader, TextSubstr};
+use crate::{
+    default_read_to_text_string, LineBreakPolicy, LineEnding, ReadText, Repair, RepairKind,
+    RepairStats, TabPolicy, TextDuplexer, TextReader, TextString, TextSubstr, TextSubstring,
+};
 use basic_text_internals::unicode::{
-    BEL, BOM, CAN, CGJ, DEL, ESC, LS, MAX_UTF8_SIZE, NEL, NORMALIZATION_BUFFER_SIZE, PS,
+    BOM, CGJ, ESC, LS, MAX_UTF8_SIZE, NEL, NORMALIZATION_BUFFER_SIZE, PS,
+};
+use basic_text_internals::unicode_normalization::char::{
+    canonical_combining_class, is_public_assigned,
 };
-use basic_text_internals::unicode_normalization::char::is_public_assigned;
 use basic_text_internals::unicode_normalization::{
-    is_nfc_stream_safe_quick, IsNormalized, Recompositions, Replacements, StreamSafe,
-    UnicodeNormalization,
+    is_nfc_quick, is_nfc_stream_safe_quick, IsNormalized,
 };
 use basic_text_internals::{
-    is_basic_text_end, is_basic_text_start, replace, IsolateUnassigned, PreNormalization,
+    guard_end, is_basic_text_start, is_stream_safe_boundary, push_ground_char, InputState as State,
+    StreamSafeNfc,
 };
 use layered_io::{default_read, HalfDuplexLayered, Status, WriteLayered};
 use std::cmp::max;
 use std::collections::{vec_deque, VecDeque};
+use std::iter::once;
 use std::mem::take;
+use std::ops::Range;
 use std::{io, str};
+use unicode_segmentation::GraphemeCursor;
+use unicode_width::UnicodeWidthChar;
 use utf8_io::{ReadStrLayered, WriteStr};
 
 /// Abstract over `TextReader` and the reader half of `TextDuplexer`.
@@ -77,8 +86,7 @@ pub(crate) struct TextInput {
     queue: VecDeque<char>,
 
     /// An iterator over the chars in `self.queue`.
-    ssnfc_iter:
-        Recompositions<StreamSafe<Replacements<IsolateUnassigned<vec_deque::IntoIter<char>>>>>,
+    ssnfc_iter: StreamSafeNfc<vec_deque::IntoIter<char>>,
 
     /// The number of characters in the queue which are already verified to be
     /// Stream-Safe NFC and can skip normalization.
@@ -95,14 +103,62 @@ pub(crate) struct TextInput {
     /// For ignoring BOM at the start of a stream.
     at_start: bool,
 
-    /// NEL compatibility mode.
-    nel_compatibility: bool,
+    /// Whether a leading BOM was removed.
+    pub(crate) had_bom: bool,
+
+    /// The line endings seen in the input so far, before translation.
+    pub(crate) line_ending: Option<LineEnding>,
+
+    /// Which of NEL, LS, and PS are translated into newlines.
+    line_break_policy: LineBreakPolicy,
+
+    /// Form-feed compatibility mode.
+    form_feed_compatibility: bool,
 
-    /// LSPS compatibility mode.
-    lsps_compatibility: bool,
+    /// What to do with tabs.
+    tab_policy: TabPolicy,
+
+    /// When expanding tabs, the display column of the end of the current
+    /// line so far.
+    column: usize,
 
     /// Control-code and escape-sequence state machine.
     state: State,
+
+    /// Counts of the repairs performed so far.
+    pub(crate) stats: RepairStats,
+
+    /// If present, a list of the repairs performed so far.
+    pub(crate) repairs: Option<Vec<Repair>>,
+
+    /// The number of bytes of input processed so far.
+    consumed: usize,
+
+    /// If present, the escape sequences removed so far.
+    pub(crate) escape_sequences: Option<Vec<String>>,
+
+    /// If present, the maximum number of bytes of input which may be
+    /// buffered without a normalization boundary.
+    max_buffer: Option<usize>,
+
+    /// The number of bytes of input read since the last normalization
+    /// boundary.
+    unbounded_len: usize,
+
+    /// If present, the input byte offsets of the scalar values output so far.
+    pub(crate) source_offsets: Option<Vec<usize>>,
+
+    /// When capturing source offsets, the input byte offsets of the scalar
+    /// values in `self.queue`.
+    offsets: VecDeque<usize>,
+
+    /// When capturing source offsets, the input byte offsets of the scalar
+    /// values remaining in `self.ssnfc_iter`.
+    normalized_offsets: VecDeque<usize>,
+
+    /// The input byte offset of the scalar value which started the current
+    /// `state`.
+    state_offset: usize,
 }
 
 impl TextInput {
@@ -113,35 +169,89 @@ impl TextInput {
         Self {
             raw_string: String::new(),
             queue,
-            ssnfc_iter: VecDeque::<char>::new()
-                .into_iter()
-                .isolate_unassigned()
-                .cjk_compat_variants()
-                .stream_safe()
-                .nfc(),
+            ssnfc_iter: StreamSafeNfc::new(VecDeque::new().into_iter()),
             quick: 0,
             pending_status: Status::active(),
             expect_starter: true,
             at_start: true,
-            nel_compatibility: false,
-            lsps_compatibility: false,
+            had_bom: false,
+            line_ending: None,
+            line_break_policy: LineBreakPolicy::empty(),
+            form_feed_compatibility: false,
+            tab_policy: TabPolicy::Preserve,
+            column: 0,
             state: State::Ground(true),
+            stats: RepairStats::new(),
+            repairs: None,
+            consumed: 0,
+            escape_sequences: None,
+            max_buffer: None,
+            unbounded_len: 0,
+            source_offsets: None,
+            offsets: VecDeque::new(),
+            normalized_offsets: VecDeque::new(),
+            state_offset: 0,
         }
     }
 
-    /// Construct a new instance of `TextInput` in NEL compatibility mode.
+    /// Start capturing the input byte offsets of the scalar values output.
+    /// Scalar values which are already queued are attributed to the current
+    /// input position.
+    pub(crate) fn capture_source_offsets(&mut self) {
+        if self.source_offsets.is_none() {
+            self.source_offsets = Some(Vec::new());
+            self.offsets.clear();
+            self.offsets.resize(self.queue.len(), self.consumed);
+        }
+    }
+
+    /// Construct a new instance of `TextInput` which translates the scalar
+    /// values selected by `line_break_policy` into newlines.
+    #[inline]
+    pub(crate) fn with_line_break_policy(line_break_policy: LineBreakPolicy) -> Self {
+        let mut result = Self::new();
+        result.line_break_policy = line_break_policy;
+        result
+    }
+
+    /// Construct a new instance of `TextInput` in form-feed compatibility
+    /// mode.
+    #[inline]
+    pub(crate) fn with_form_feed_compatibility() -> Self {
+        let mut result = Self::new();
+        result.form_feed_compatibility = true;
+        result
+    }
+
+    /// Construct a new instance of `TextInput` which treats a leading U+FEFF
+    /// (BOM) as content, translating it like any other U+FEFF.
     #[inline]
-    pub(crate) fn with_nel_compatibility() -> Self {
+    pub(crate) fn with_bom_as_content() -> Self {
         let mut result = Self::new();
-        result.nel_compatibility = true;
+        result.at_start = false;
         result
     }
 
-    /// Construct a new instance of `TextInput` in LSPS compatibility mode.
+    /// Construct a new instance of `TextInput` which handles tabs according
+    /// to `tab_policy`.
     #[inline]
-    pub(crate) fn with_lsps_compatibility() -> Self {
+    pub(crate) fn with_tab_policy(tab_policy: TabPolicy) -> Self {
+        assert_ne!(
+            tab_policy,
+            TabPolicy::ExpandTo(0),
+            "tab width must be nonzero"
+        );
         let mut result = Self::new();
-        result.lsps_compatibility = true;
+        result.tab_policy = tab_policy;
+        result
+    }
+
+    /// Construct a new instance of `TextInput` which fails if more than
+    /// `limit` bytes of input are read without a line boundary.
+    #[inline]
+    pub(crate) fn with_max_buffer(limit: usize) -> Self {
+        let mut result = Self::new();
+        result.max_buffer = Some(limit);
         result
     }
 
@@ -200,6 +310,8 @@ impl TextInput {
         internals: &mut impl TextReaderInternals<Inner>,
         buf: &mut TextSubstr,
     ) -> io::Result<usize> {
+        internals.impl_().check_text_output()?;
+
         // Safety: This is a Basic Text stream so we can read directly into a
         // `TextSubstr`.
         internals.read(unsafe { buf.as_bytes_mut() })
@@ -210,6 +322,8 @@ impl TextInput {
         internals: &mut impl TextReaderInternals<Inner>,
         buf: &mut TextSubstr,
     ) -> io::Result<()> {
+        internals.impl_().check_text_output()?;
+
         // Safety: This is a Basic Text stream so we can read directly into a
         // `TextSubstr`.
         internals.read_exact(unsafe { buf.as_bytes_mut() })
@@ -220,6 +334,8 @@ impl TextInput {
         internals: &mut impl TextReaderInternals<Inner>,
         buf: &mut TextSubstr,
     ) -> io::Result<(usize, Status)> {
+        internals.impl_().check_text_output()?;
+
         // Safety: This is a Basic Text stream so we can read directly into a
         // `TextSubstr`.
         internals.read_with_status(unsafe { buf.as_bytes_mut() })
@@ -230,25 +346,41 @@ impl TextInput {
         internals: &mut impl TextReaderInternals<Inner>,
         buf: &mut TextSubstr,
     ) -> io::Result<Status> {
+        internals.impl_().check_text_output()?;
+
         // Safety: This is a Basic Text stream so we can read directly into a
         // `TextSubstr`.
         internals.read_exact_using_status(unsafe { buf.as_bytes_mut() })
     }
 
+    #[inline]
+    pub(crate) fn read_to_text_string<Inner: ReadStrLayered>(
+        internals: &mut (impl TextReaderInternals<Inner> + ReadText),
+        buf: &mut TextString,
+    ) -> io::Result<usize> {
+        internals.impl_().check_text_output()?;
+
+        default_read_to_text_string(internals, buf)
+    }
+
     fn queue_next(&mut self) -> Option<char> {
         let quick = self.quick;
         if quick != 0 {
             self.quick = quick - 1;
+            self.pop_offset();
             self.queue.pop_front()
         } else {
             match self.ssnfc_iter.next() {
-                Some(c) => Some(c),
+                Some(c) => {
+                    self.pop_normalized_offset();
+                    Some(c)
+                }
                 None => {
                     let last_boundary = self
                         .queue
                         .iter()
                         .rev()
-                        .position(|c| matches!(*c, '\n' | CGJ))?;
+                        .position(|c| is_stream_safe_boundary(*c))?;
                     let index = self.queue.len() - last_boundary;
                     if is_nfc_stream_safe_quick(self.queue.iter().take(index).copied())
                         == IsNormalized::Yes
@@ -260,63 +392,198 @@ impl TextInput {
                             .all(is_public_assigned)
                     {
                         self.quick = index - 1;
+                        self.pop_offset();
                         self.queue.pop_front()
                     } else {
                         let tmp = self.queue.drain(..index).collect::<VecDeque<char>>();
-                        self.ssnfc_iter = tmp
-                            .into_iter()
-                            .isolate_unassigned()
-                            .cjk_compat_variants()
-                            .stream_safe()
-                            .nfc();
-                        self.ssnfc_iter.next()
+                        if self.source_offsets.is_some() {
+                            let offsets = self.offsets.drain(..index).collect::<Vec<usize>>();
+                            self.normalized_offsets = normalized_offsets(&tmp, &offsets);
+                        }
+                        self.ssnfc_iter = StreamSafeNfc::new(tmp.into_iter());
+                        let c = self.ssnfc_iter.next();
+                        if c.is_some() {
+                            self.pop_normalized_offset();
+                        }
+                        c
                     }
                 }
             }
         }
     }
 
+    /// When capturing source offsets, record the offset of a scalar value
+    /// output from the front of the queue.
+    #[inline]
+    fn pop_offset(&mut self) {
+        if let Some(source_offsets) = &mut self.source_offsets {
+            source_offsets.push(self.offsets.pop_front().unwrap_or(self.consumed));
+        }
+    }
+
+    /// When capturing source offsets, record the offset of a scalar value
+    /// output from `self.ssnfc_iter`.
+    #[inline]
+    fn pop_normalized_offset(&mut self) {
+        if let Some(source_offsets) = &mut self.source_offsets {
+            source_offsets.push(self.normalized_offsets.pop_front().unwrap_or(self.consumed));
+        }
+    }
+
+    /// When capturing source offsets, attribute the scalar values pushed onto
+    /// the queue since the last call to input byte offset `offset`.
+    #[inline]
+    fn push_offsets(&mut self, offset: usize) {
+        if self.source_offsets.is_some() {
+            self.offsets.resize(self.queue.len(), offset);
+        }
+    }
+
     fn process_raw_string(&mut self) {
-        let mut chars = self.raw_string.chars();
+        let raw_string = take(&mut self.raw_string);
+        let base = self.consumed;
+        let mut chars = raw_string.char_indices();
 
         // If we're at the start of a stream, skip over a leading BOM.
-        if take(&mut self.at_start) && self.raw_string.starts_with(BOM) {
+        if take(&mut self.at_start) && raw_string.starts_with(BOM) {
             chars.next();
+            self.had_bom = true;
+            self.record(RepairKind::BomRemoved, 0..BOM.len_utf8(), "");
         }
 
-        for c in chars {
+        let expand_tabs = matches!(self.tab_policy, TabPolicy::ExpandTo(_));
+        let is_plain = |c: char| is_plain_ascii(c) && !(expand_tabs && c == '\t');
+
+        while let Some((index, c)) = chars.next() {
+            // Fast path: plain ASCII in the ground state is copied to the
+            // queue unchanged, so skip the state machine for the whole run.
+            if matches!(self.state, State::Ground(_)) && is_plain(c) {
+                let len = raw_string.as_bytes()[index..]
+                    .iter()
+                    .take_while(|b| is_plain(char::from(**b)))
+                    .count();
+                let run = &raw_string[index..index + len];
+                self.queue.extend(run.chars());
+                if self.source_offsets.is_some() {
+                    self.offsets.extend(base + index..base + index + len);
+                }
+                if expand_tabs {
+                    if self.state == State::Ground(true) {
+                        self.column = 0;
+                    }
+                    self.column += len;
+                }
+                self.expect_starter = false;
+                self.state = State::Ground(false);
+                self.state_offset = base + index + len - 1;
+                if len > 1 {
+                    chars.nth(len - 2);
+                }
+                continue;
+            }
+
+            let offset = base + index;
+            let next = offset + c.len_utf8();
+            let mut source = offset;
             loop {
+                // Scalar values pushed while handling a state are attributed
+                // to the input which started that state.
+                self.push_offsets(source);
+                match (self.state, c) {
+                    (State::Ground(_), _) | (State::Ff, '\r') => self.state_offset = offset,
+                    _ => (),
+                }
+                source = match (self.state, c) {
+                    (State::Ground(_), _) | (State::Ff, '\n') => offset,
+                    _ => self.state_offset,
+                };
+
+                let prev = self.state;
+                if expand_tabs && self.state == State::Ground(true) {
+                    self.column = 0;
+                }
                 match (self.state, c) {
                     (State::Ground(_), c) => match c {
                         '\n' => {
                             self.queue.push_back('\n');
                             self.expect_starter = false;
                             self.state = State::Ground(true);
+                            self.saw_line_ending(LineEnding::Lf);
+                        }
+                        '\r' => {
+                            self.record(RepairKind::NewlineNormalized, offset..next, "\n");
+                            self.state = State::Cr;
+                        }
+                        '\x0c' if self.form_feed_compatibility => {
+                            // Emit the page break on a line by itself.
+                            if self.state != State::Ground(true) {
+                                self.queue.push_back('\n');
+                            }
+                            self.queue.push_back('\x0c');
+                            self.expect_starter = false;
+                            self.state = State::Ff;
+                        }
+                        '\x0c' => {
+                            self.record(RepairKind::FormFeedReplaced, offset..next, " ");
+                            self.state = State::Ff;
+                        }
+                        '\t' if expand_tabs => {
+                            if let TabPolicy::ExpandTo(width) = self.tab_policy {
+                                let n = width - self.column % width;
+                                let spaces = " ".repeat(n);
+                                self.queue.extend(spaces.chars());
+                                self.column += n;
+                                self.expect_starter = false;
+                                self.state = State::Ground(false);
+                                self.record(RepairKind::ScalarReplaced, offset..next, &spaces);
+                            }
+                        }
+                        ESC => {
+                            self.record(RepairKind::EscapeSequenceRemoved, offset..next, "");
+                            if let Some(escape_sequences) = &mut self.escape_sequences {
+                                escape_sequences.push(ESC.to_string());
+                            }
+                            self.state = State::Esc;
                         }
-                        '\r' => self.state = State::Cr,
-                        '\x0c' => self.state = State::Ff,
-                        ESC => self.state = State::Esc,
                         mut c => {
                             self.state = State::Ground(false);
-                            if (self.nel_compatibility && c == NEL)
-                                || (self.lsps_compatibility && matches!(c, LS | PS))
-                            {
+                            let mut newline = false;
+                            let policy = match c {
+                                NEL => LineBreakPolicy::NEL,
+                                LS => LineBreakPolicy::LS,
+                                PS => LineBreakPolicy::PS,
+                                _ => LineBreakPolicy::empty(),
+                            };
+                            if !policy.is_empty() && self.line_break_policy.contains(policy) {
                                 c = '\n';
                                 self.state = State::Ground(true);
+                                newline = true;
                             }
                             let pos = self.queue.len();
-                            replace(c, &mut self.queue);
-
-                            // Prepend a CGJ if needed to guard a non-starter.
-                            if take(&mut self.expect_starter)
-                                && !self
+                            let guarded =
+                                push_ground_char(c, &mut self.queue, &mut self.expect_starter);
+                            let start = pos + usize::from(guarded);
+                            let replaced = self.queue.len() != start + 1 || self.queue[start] != c;
+                            if expand_tabs {
+                                self.column += self
                                     .queue
-                                    .get(pos)
-                                    .copied()
-                                    .map(is_basic_text_start)
-                                    .unwrap_or(true)
-                            {
-                                self.queue.insert(pos, CGJ);
+                                    .range(start..)
+                                    .map(|c| c.width().unwrap_or(0))
+                                    .sum::<usize>();
+                            }
+                            if guarded {
+                                self.record(RepairKind::CgjInserted, offset..offset, "\u{34f}");
+                            }
+
+                            if newline {
+                                self.record(RepairKind::NewlineNormalized, offset..next, "\n");
+                            } else if replaced {
+                                let replacement = if self.repairs.is_some() {
+                                    self.queue.range(start..).collect::<String>()
+                                } else {
+                                    String::new()
+                                };
+                                self.record(RepairKind::ScalarReplaced, offset..next, &replacement);
                             }
                         }
                     },
@@ -326,59 +593,111 @@ impl TextInput {
                         self.expect_starter = false;
                         self.state = State::Ground(true);
                         if c != '\n' {
+                            self.saw_line_ending(LineEnding::Cr);
                             continue;
                         }
+                        self.saw_line_ending(LineEnding::CrLf);
+                        self.extend_repair(next);
                     }
 
+                    (State::Ff, c) if self.form_feed_compatibility => match c {
+                        '\n' => {
+                            self.queue.push_back('\n');
+                            self.state = State::Ground(true);
+                            self.saw_line_ending(LineEnding::Lf);
+                        }
+                        '\r' => {
+                            self.record(RepairKind::NewlineNormalized, offset..next, "\n");
+                            self.state = State::Cr;
+                        }
+                        _ => {
+                            self.queue.push_back('\n');
+                            self.state = State::Ground(true);
+                            continue;
+                        }
+                    },
+
                     (State::Ff, c) => {
                         if c != '\x0c' {
                             if c != '\n' && c != '\r' {
                                 self.queue.push_back(' ');
+                                self.column += 1;
+                            } else {
+                                self.update_repair("");
                             }
                             self.expect_starter = false;
                             self.state = State::Ground(false);
                             continue;
                         }
+                        self.extend_repair(next);
                     }
 
-                    (State::Esc, '[') => self.state = State::CsiStart,
-                    (State::Esc, ']') => self.state = State::Osc,
-                    (State::Esc, ESC) => self.state = State::Esc,
-                    (State::Esc, c) if matches!(c, '@'..='~' | CAN) => {
-                        self.state = State::Ground(false);
-                    }
-                    (State::Esc, _) => {
-                        self.state = State::Ground(false);
-                        continue;
-                    }
-
-                    (State::CsiStart, '[') => self.state = State::Linux,
-                    (State::CsiStart, c) | (State::Csi, c) if matches!(c, ' '..='?') => {
-                        self.state = State::Csi;
-                    }
-                    (State::CsiStart, c) | (State::Csi, c) if matches!(c, '@'..='~') => {
-                        self.state = State::Ground(false);
-                    }
-                    (State::CsiStart, CAN) | (State::Csi, CAN) => self.state = State::Ground(false),
-                    (State::CsiStart, _) | (State::Csi, _) => {
-                        self.state = State::Ground(false);
-                        continue;
+                    (_, c) => {
+                        if !self.state.escape_sequence_step(c) {
+                            continue;
+                        }
                     }
+                }
 
-                    (State::Osc, BEL) | (State::Osc, CAN) => self.state = State::Ground(false),
-                    (State::Osc, ESC) => self.state = State::Esc,
-                    (State::Osc, _) => (),
-
-                    (State::Linux, c) if matches!(c, '\0'..=DEL) => {
-                        self.state = State::Ground(false);
-                    }
-                    (State::Linux, _) => {
-                        self.state = State::Ground(false);
-                        continue;
+                // Scalar values consumed within an escape sequence are part of
+                // the sequence being removed.
+                if prev.in_escape_sequence() {
+                    self.extend_repair(next);
+                    if let Some(escape_sequence) = self
+                        .escape_sequences
+                        .as_mut()
+                        .and_then(|escape_sequences| escape_sequences.last_mut())
+                    {
+                        escape_sequence.push(c);
                     }
                 }
                 break;
             }
+            self.push_offsets(source);
+        }
+
+        self.consumed += raw_string.len();
+        self.raw_string = raw_string;
+    }
+
+    /// Note a line ending seen in the input.
+    #[inline]
+    fn saw_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = match self.line_ending {
+            None => Some(line_ending),
+            Some(prev) if prev == line_ending => Some(line_ending),
+            Some(_) => Some(LineEnding::Mixed),
+        };
+    }
+
+    /// Count a repair, and if repairs are being recorded, record it.
+    #[inline]
+    fn record(&mut self, kind: RepairKind, byte_range: Range<usize>, replacement: &str) {
+        self.stats.count(kind);
+        if let Some(repairs) = &mut self.repairs {
+            repairs.push(Repair {
+                byte_range,
+                kind,
+                replacement: replacement.to_owned(),
+            });
+        }
+    }
+
+    /// If repairs are being recorded, extend the most recent one to cover
+    /// input up to `end`.
+    #[inline]
+    fn extend_repair(&mut self, end: usize) {
+        if let Some(repair) = self.repairs.as_mut().and_then(|repairs| repairs.last_mut()) {
+            repair.byte_range.end = end;
+        }
+    }
+
+    /// If repairs are being recorded, change the replacement text of the
+    /// most recent one.
+    #[inline]
+    fn update_repair(&mut self, replacement: &str) {
+        if let Some(repair) = self.repairs.as_mut().and_then(|repairs| repairs.last_mut()) {
+            repair.replacement = replacement.to_owned();
         }
     }
 
@@ -389,76 +708,263 @@ impl TextInput {
         if buf.len() < NORMALIZATION_BUFFER_SIZE {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                format!("buffer for text input is {} bytes; at least NORMALIZATION_BUFFER_SIZE bytes are required", buf.len())
+                format!("buffer for text input is {} bytes; at least NORMALIZATION_BUFFER_SIZE (TextReader::MIN_BUFFER_SIZE) bytes are required", buf.len())
             ));
         }
 
         let mut nread = 0;
-        while let Some(c) = internals.impl_().queue_next() {
-            nread += c.encode_utf8(&mut buf[nread..]).len();
-            if buf.len() - nread < MAX_UTF8_SIZE {
-                // Write out single-byte codepoints to preserve UTF-8 validity.
-                clear_to_char_boundary(&mut buf[nread..]);
-                return Ok((nread, Status::active()));
-            }
+        if let Some(result) = internals.impl_().read_queued(buf, &mut nread) {
+            return Ok(result);
         }
-        if internals.impl_().pending_status != Status::active() {
-            internals.impl_().pending_status = Status::active();
-            internals.impl_().expect_starter = true;
-
-            // Write out single-byte codepoints to preserve UTF-8 validity.
-            clear_to_char_boundary(&mut buf[nread..]);
 
-            return Ok((nread, internals.impl_().pending_status));
-        }
+        let (raw_string, status) = Self::read_raw(internals)?;
+        internals.impl_().check_max_buffer(&raw_string)?;
+        internals.impl_().check_tab_policy(&raw_string)?;
 
-        let mut raw_bytes = take(&mut internals.impl_().raw_string).into_bytes();
-        raw_bytes.resize(4096, 0_u8);
-        let (size, status) = internals.inner_mut().read_with_status(&mut raw_bytes)?;
-        raw_bytes.resize(size, 0);
-        // Safety: This is a UTF-8 stream so we can read into a `String`.
-        internals.impl_().raw_string = unsafe { String::from_utf8_unchecked(raw_bytes) };
+        Ok(internals
+            .impl_()
+            .read_processed(raw_string, status, buf, nread))
+    }
 
-        internals.impl_().process_raw_string();
+    /// Read up to and including the next newline, and append it to `buf`.
+    ///
+    /// Translated scalar values after the newline stay queued for the next
+    /// read.
+    pub(crate) fn read_line_text<Inner: ReadStrLayered>(
+        internals: &mut impl TextReaderInternals<Inner>,
+        buf: &mut TextString,
+    ) -> io::Result<usize> {
+        internals.impl_().check_text_output()?;
 
-        if status != Status::active() {
-            match internals.impl_().state {
-                State::Ground(_) => {}
-                State::Cr => {
-                    internals.impl_().queue.push_back('\n');
-                    internals.impl_().expect_starter = false;
-                    internals.impl_().state = State::Ground(true);
+        let mut line = String::new();
+        loop {
+            while let Some(c) = internals.impl_().queue_next() {
+                line.push(c);
+                if c == '\n' {
+                    return Self::append_line(buf, &line);
                 }
-                State::Ff => {
-                    internals.impl_().queue.push_back(' ');
-                    internals.impl_().expect_starter = false;
-                    internals.impl_().state = State::Ground(false);
+            }
+
+            let input = internals.impl_();
+            if input.pending_status != Status::active() {
+                let status = input.pending_status;
+                input.pending_status = Status::active();
+                input.expect_starter = true;
+                if status.is_end() {
+                    return Self::append_line(buf, &line);
                 }
-                State::Esc | State::CsiStart | State::Csi | State::Osc | State::Linux => {
-                    internals.impl_().state = State::Ground(false);
+                continue;
+            }
+
+            // The line itself may contain boundaries, so check its length
+            // separately.
+            if let Some(limit) = input.max_buffer {
+                if line.len() > limit {
+                    return Err(max_buffer_error(limit));
                 }
             }
 
-            // If the stream ends in a non-ending char, append a CGJ.
-            if let Some(last) = internals.impl_().queue.back() {
-                if !is_basic_text_end(*last) {
-                    internals.impl_().queue.push_back(CGJ);
+            let (raw_string, status) = Self::read_raw(internals)?;
+            let input = internals.impl_();
+            input.check_max_buffer(&raw_string)?;
+            input.check_tab_policy(&raw_string)?;
+            input.process(raw_string, status);
+            input.pending_status = status;
+        }
+    }
+
+    /// Read exactly `n` extended grapheme clusters and append them to `buf`.
+    ///
+    /// Translated scalar values after the last grapheme cluster stay queued
+    /// for the next read.
+    pub(crate) fn read_exact_graphemes<Inner: ReadStrLayered>(
+        internals: &mut impl TextReaderInternals<Inner>,
+        n: usize,
+        buf: &mut TextSubstring,
+    ) -> io::Result<()> {
+        internals.impl_().check_text_output()?;
+
+        if n == 0 {
+            return Ok(());
+        }
+
+        let mut graphemes = String::new();
+        let mut count = 0;
+        loop {
+            while let Some(c) = internals.impl_().queue_next() {
+                let len = graphemes.len();
+                graphemes.push(c);
+
+                // Grapheme boundaries depend only on the scalar values before
+                // them and the one immediately after, so this is a complete
+                // grapheme cluster.
+                if len != 0
+                    && GraphemeCursor::new(len, graphemes.len(), true)
+                        .is_boundary(&graphemes, 0)
+                        .unwrap()
+                {
+                    count += 1;
+                    if count == n {
+                        graphemes.truncate(len);
+                        internals.impl_().unqueue(c);
+                        buf.0.push_str(&graphemes);
+                        return Ok(());
+                    }
                 }
             }
 
-            if status.is_end() {
-                // If the stream doesn't end in a newline, append one.
-                if internals.impl_().state != State::Ground(true) {
-                    internals.impl_().queue.push_back('\n');
-                    internals.impl_().expect_starter = false;
-                    internals.impl_().state = State::Ground(true);
+            let input = internals.impl_();
+            if input.pending_status != Status::active() {
+                let status = input.pending_status;
+                input.pending_status = Status::active();
+                input.expect_starter = true;
+                if status.is_end() {
+                    if !graphemes.is_empty() {
+                        count += 1;
+                    }
+                    if count != n {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "failed to read the requested number of graphemes",
+                        ));
+                    }
+                    buf.0.push_str(&graphemes);
+                    return Ok(());
                 }
+                continue;
+            }
+
+            let (raw_string, status) = Self::read_raw(internals)?;
+            let input = internals.impl_();
+            input.check_max_buffer(&raw_string)?;
+            input.check_tab_policy(&raw_string)?;
+            input.process(raw_string, status);
+            input.pending_status = status;
+        }
+    }
+
+    /// Return `c`, which was just returned by `queue_next`, to the front of
+    /// the queue.
+    fn unqueue(&mut self, c: char) {
+        self.queue.push_front(c);
+        self.quick += 1;
+        if let Some(source_offsets) = &mut self.source_offsets {
+            if let Some(offset) = source_offsets.pop() {
+                self.offsets.push_front(offset);
+            }
+        }
+    }
+
+    fn append_line(buf: &mut TextString, line: &str) -> io::Result<usize> {
+        let start = buf.0.len();
+        if let Some(c) = line.chars().next() {
+            // In the stream, a line follows a newline, so it may start with
+            // a non-starter. If it isn't appended after a newline, guard it
+            // with a CGJ.
+            if !is_basic_text_start(c) && !buf.0.ends_with('\n') {
+                buf.0.push(CGJ);
+            }
+        }
+        buf.0.push_str(line);
+        Ok(buf.0.len() - start)
+    }
+
+    /// If a maximum buffer size is set, check that reading `raw_string`
+    /// doesn't exceed it before reaching a boundary at which buffered input
+    /// can be released.
+    fn check_max_buffer(&mut self, raw_string: &str) -> io::Result<()> {
+        if let Some(limit) = self.max_buffer {
+            match raw_string.rfind(|c| matches!(c, '\n' | '\r' | CGJ)) {
+                Some(i) => self.unbounded_len = raw_string.len() - i,
+                None => self.unbounded_len += raw_string.len(),
             }
+            if self.unbounded_len > limit {
+                return Err(max_buffer_error(limit));
+            }
+        }
+        Ok(())
+    }
+
+    /// If tabs are rejected, check that `raw_string` doesn't contain any.
+    fn check_tab_policy(&self, raw_string: &str) -> io::Result<()> {
+        if self.tab_policy == TabPolicy::Reject && raw_string.contains('\t') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "text input contains a tab, which the tab policy rejects",
+            ));
+        }
+        Ok(())
+    }
+
+    /// In form-feed compatibility mode, U+C is preserved, so the output isn't
+    /// Basic Text, and may only be read as bytes or as a `str`.
+    fn check_text_output(&self) -> io::Result<()> {
+        if self.form_feed_compatibility {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "form-feed compatibility mode output isn't Basic Text, and can't be read as text",
+            ));
         }
+        Ok(())
+    }
+
+    /// Read a chunk of raw input from the underlying stream.
+    fn read_raw<Inner: ReadStrLayered>(
+        internals: &mut impl TextReaderInternals<Inner>,
+    ) -> io::Result<(String, Status)> {
+        let mut raw_bytes = take(&mut internals.impl_().raw_string).into_bytes();
+        raw_bytes.resize(4096, 0_u8);
+        let (size, status) = internals.inner_mut().read_with_status(&mut raw_bytes)?;
+        raw_bytes.resize(size, 0);
+        // Safety: This is a UTF-8 stream so we can read into a `String`.
+        let raw_string = unsafe { String::from_utf8_unchecked(raw_bytes) };
+        Ok((raw_string, status))
+    }
+
+    /// Write translated scalar values which are already queued into `buf`,
+    /// starting at `*nread`. Return `Some` if `buf` is full or there is a
+    /// pending status to report, or `None` if more input is needed.
+    pub(crate) fn read_queued(
+        &mut self,
+        buf: &mut [u8],
+        nread: &mut usize,
+    ) -> Option<(usize, Status)> {
+        while let Some(c) = self.queue_next() {
+            *nread += c.encode_utf8(&mut buf[*nread..]).len();
+            if buf.len() - *nread < MAX_UTF8_SIZE {
+                // Write out single-byte codepoints to preserve UTF-8 validity.
+                clear_to_char_boundary(&mut buf[*nread..]);
+                return Some((*nread, Status::active()));
+            }
+        }
+        if self.pending_status != Status::active() {
+            self.pending_status = Status::active();
+            self.expect_starter = true;
+
+            // Write out single-byte codepoints to preserve UTF-8 validity.
+            clear_to_char_boundary(&mut buf[*nread..]);
+
+            return Some((*nread, self.pending_status));
+        }
+
+        None
+    }
+
+    /// Translate `raw_string`, read from the underlying stream with status
+    /// `status`, and write the result into `buf`, following the `nread`
+    /// bytes already written by `read_queued`.
+    pub(crate) fn read_processed(
+        &mut self,
+        raw_string: String,
+        status: Status,
+        buf: &mut [u8],
+        mut nread: usize,
+    ) -> (usize, Status) {
+        self.process(raw_string, status);
 
         let mut queue_empty = false;
         loop {
-            match internals.impl_().queue_next() {
+            match self.queue_next() {
                 Some(c) => nread += c.encode_utf8(&mut buf[nread..]).len(),
                 None => {
                     queue_empty = true;
@@ -473,18 +979,68 @@ impl TextInput {
         // Write out single-byte codepoints to preserve UTF-8 validity.
         clear_to_char_boundary(&mut buf[nread..]);
 
-        Ok((
+        (
             nread,
             if queue_empty {
                 if status != Status::active() {
-                    internals.impl_().expect_starter = true;
+                    self.expect_starter = true;
                 }
                 status
             } else {
-                internals.impl_().pending_status = status;
+                self.pending_status = status;
                 Status::active()
             },
-        ))
+        )
+    }
+
+    /// Translate `raw_string`, read from the underlying stream with status
+    /// `status`, into the queue.
+    fn process(&mut self, raw_string: String, status: Status) {
+        self.raw_string = raw_string;
+        self.process_raw_string();
+
+        if status != Status::active() {
+            match self.state {
+                State::Ground(_) => {}
+                State::Cr => {
+                    self.queue.push_back('\n');
+                    self.expect_starter = false;
+                    self.state = State::Ground(true);
+                    self.saw_line_ending(LineEnding::Cr);
+                }
+                State::Ff => {
+                    if self.form_feed_compatibility {
+                        self.queue.push_back('\n');
+                        self.state = State::Ground(true);
+                    } else {
+                        self.queue.push_back(' ');
+                        self.expect_starter = false;
+                        self.state = State::Ground(false);
+                    }
+                }
+                State::Esc | State::CsiStart | State::Csi | State::Osc | State::Linux => {
+                    self.state = State::Ground(false);
+                }
+            }
+            self.push_offsets(self.state_offset);
+
+            // If the stream ends in a non-ending char, append a CGJ.
+            let consumed = self.consumed;
+            if guard_end(&mut self.queue) {
+                self.record(RepairKind::CgjInserted, consumed..consumed, "\u{34f}");
+            }
+
+            if status.is_end() {
+                // If the stream doesn't end in a newline, append one.
+                if self.state != State::Ground(true) {
+                    self.record(RepairKind::NewlineAppended, consumed..consumed, "\n");
+                    self.queue.push_back('\n');
+                    self.expect_starter = false;
+                    self.state = State::Ground(true);
+                }
+            }
+            self.push_offsets(consumed);
+        }
     }
 
     #[inline]
@@ -502,7 +1058,9 @@ impl TextInput {
         // Don't enforce a trailing newline.
         internals.impl_().state = State::Ground(true);
 
-        assert!(internals.impl_().queue.is_empty());
+        // Discard any input still buffered after an error, such as the
+        // `max_buffer` limit being exceeded.
+        internals.impl_().queue.clear();
 
         internals.inner_mut().abandon();
     }
@@ -535,6 +1093,49 @@ impl TextInput {
     }
 }
 
+/// Compute the input byte offsets of the scalar values produced by
+/// normalizing `chars`, whose input byte offsets are `offsets`.
+///
+/// Normalization can compose, decompose, and reorder scalar values, so each
+/// scalar value produced is attributed to the start of the sequence it was
+/// normalized with, which extends up to the next scalar value which
+/// normalization can't combine with anything before it.
+fn normalized_offsets(chars: &VecDeque<char>, offsets: &[usize]) -> VecDeque<usize> {
+    let mut result = VecDeque::new();
+    let mut start = 0;
+    for i in 1..=chars.len() {
+        if i == chars.len() || is_normalization_boundary(chars[i - 1], chars[i]) {
+            let n = StreamSafeNfc::new(chars.range(start..i).copied()).count();
+            result.resize(result.len() + n, offsets[start]);
+            start = i;
+        }
+    }
+    result
+}
+
+/// Test whether normalization of `c` is independent of the scalar values
+/// before it, given that the one immediately before it is `prev`.
+#[inline]
+fn is_normalization_boundary(prev: char, c: char) -> bool {
+    // Unassigned scalar values are isolated with CGJs depending on their
+    // neighbors, so don't split next to them.
+    canonical_combining_class(c) == 0
+        && is_nfc_quick(once(c)) == IsNormalized::Yes
+        && is_public_assigned(prev)
+        && is_public_assigned(c)
+}
+
+#[cold]
+fn max_buffer_error(limit: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "text input exceeded the maximum buffer size of {} bytes without a line boundary",
+            limit
+        ),
+    )
+}
+
 #[inline]
 fn clear_to_char_boundary(buf: &mut [u8]) {
     for b in buf {
@@ -545,6 +1146,13 @@ fn clear_to_char_boundary(buf: &mut [u8]) {
     }
 }
 
+/// Test whether `c` is ASCII which is passed through to the output
+/// unchanged, which is everything except control codes other than tab.
+#[inline]
+fn is_plain_ascii(c: char) -> bool {
+    matches!(c, ' '..='~' | '\t')
+}
+
 #[test]
 fn test_clear_to_char_boundary() {
     let mut buf = vec![7, 7, 0x80, 0x80, 7];
@@ -568,30 +1176,3 @@ fn test_is_char_boundary() {
     assert!(is_char_boundary(0xcf));
     assert!(is_char_boundary(0xff));
 }
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum State {
-    // Default state. Boolean is true iff we just saw a '\n'.
-    Ground(bool),
-
-    // After a '\r'.
-    Cr,
-
-    // After a '\x0c'.
-    Ff,
-
-    // After a '\x1b'.
-    Esc,
-
-    // Immediately after a "\x1b[".
-    CsiStart,
-
-    // Within a sequence started by "\x1b[".
-    Csi,
-
-    // Within a sequence started by "\x1b]".
-    Osc,
-
-    // After a "\x1b[[".
-    Linux,
-}
@@ -1,14 +1,12 @@
 //! Shared implementation for `TextWriter` and the writer half of
 //! `TextDuplexer`.
 
-use crate::{TextDuplexer, TextSubstr, TextWriter};
-use basic_text_internals::unicode::{BOM, ESC, MAX_UTF8_SIZE, SUB};
+use crate::{TextDuplexer, TextStr, TextSubstr, TextWriter, WriteStats};
+use basic_text_internals::unicode::{BOM, ESC, FF, MAX_UTF8_SIZE, SUB};
 use basic_text_internals::unicode_normalization::char::is_public_assigned;
-use basic_text_internals::unicode_normalization::{
-    is_nfc_stream_safe_quick, IsNormalized, UnicodeNormalization,
-};
+use basic_text_internals::unicode_normalization::{is_nfc_stream_safe_quick, IsNormalized};
 use basic_text_internals::{
-    is_basic_text_end, is_basic_text_start, BasicTextError, PreNormalization,
+    is_basic_text_end, is_basic_text_start, BasicTextError, PreNormalization, StreamSafeNfc,
 };
 #[cfg(can_vector)]
 use layered_io::default_is_write_vectored;
@@ -17,9 +15,13 @@ use layered_io::default_write_all_vectored;
 use layered_io::{default_write_vectored, HalfDuplexLayered, WriteLayered};
 use std::cell::RefCell;
 use std::io::{self, Write};
-use std::mem::take;
+use std::mem::{take, ManuallyDrop};
+use std::ptr;
 use std::rc::Rc;
 use std::str;
+use std::thread;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 use utf8_io::{ReadStrLayered, WriteStr};
 
 /// Abstract over `TextWriter` and the writer half of `TextDuplexer`.
@@ -45,7 +47,14 @@ impl<Inner: WriteStr + WriteLayered> TextWriterInternals<Inner> for TextWriter<I
     }
 
     fn into_inner(self) -> Inner {
-        self.inner
+        // `TextWriter` implements `Drop`, so move the fields out manually.
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so `output` is dropped and
+        // `inner` is moved out exactly once each.
+        unsafe {
+            ptr::drop_in_place(&mut this.output);
+            ptr::read(&this.inner)
+        }
     }
 
     fn write_str(&mut self, s: &str) -> io::Result<()> {
@@ -91,11 +100,53 @@ pub(crate) struct TextOutput {
     /// Are `ESC [ ... m`-style color sequences enabled?
     ansi_color: bool,
 
+    /// When enabled, a U+C (FF) on a line by itself is permitted as a page
+    /// break.
+    form_feed_compatibility: bool,
+
+    /// Control codes which are permitted to pass through verbatim.
+    allowed_controls: Vec<char>,
+
+    /// When enabled, closing a stream which doesn't end in a newline
+    /// appends one.
+    auto_final_newline: bool,
+
     /// Control-code and escape-sequence state machine.
     state: State,
 
     /// An in-progress escape sequence.
     escape_sequence: String,
+
+    /// An error detected after a valid prefix of a `write` buffer, which is
+    /// reported by the next call to `write`, along with the offset past the
+    /// end of the valid prefix at which it was detected.
+    pending_error: Option<(usize, BasicTextError)>,
+
+    /// When a `write` fails, the offset in its buffer at which the error was
+    /// detected.
+    error_offset: usize,
+
+    /// Set once an error has been reported or the stream has been abandoned,
+    /// after which a missing trailing newline isn't reported on drop.
+    poisoned: bool,
+
+    /// When set, lines wider than this many display columns are wrapped.
+    wrap_columns: Option<usize>,
+
+    /// When wrapping, the display width of the output already written on the
+    /// current line.
+    column: usize,
+
+    /// When wrapping, output on the current line which is held back until we
+    /// know where to break it.
+    wrap_pending: String,
+
+    /// Counts of the output written so far.
+    pub(crate) stats: WriteStats,
+
+    /// When counting grapheme clusters, the last grapheme cluster written,
+    /// which may be extended by the next write.
+    last_grapheme: Option<String>,
 }
 
 impl TextOutput {
@@ -107,8 +158,19 @@ impl TextOutput {
             crlf_compatibility: false,
             expect_starter: true,
             ansi_color: false,
+            form_feed_compatibility: false,
+            allowed_controls: Vec::new(),
+            auto_final_newline: false,
             state: State::Ground(Ground::Newline),
             escape_sequence: String::new(),
+            pending_error: None,
+            error_offset: 0,
+            poisoned: false,
+            wrap_columns: None,
+            column: 0,
+            wrap_pending: String::new(),
+            stats: WriteStats::new(),
+            last_grapheme: None,
         }
     }
 
@@ -129,6 +191,42 @@ impl TextOutput {
         result
     }
 
+    /// Like `new`, but permits U+C (FF) as a page break, when it appears at
+    /// the beginning of a line and is immediately followed by a newline.
+    #[inline]
+    pub(crate) const fn with_form_feed_compatibility() -> Self {
+        let mut result = Self::new();
+        result.form_feed_compatibility = true;
+        result
+    }
+
+    /// Like `new`, but permits the control codes in `allowed_controls` to
+    /// pass through verbatim.
+    #[inline]
+    pub(crate) fn with_allowed_controls(allowed_controls: &[char]) -> Self {
+        let mut result = Self::new();
+        result.allowed_controls = allowed_controls.to_vec();
+        result
+    }
+
+    /// Like `new`, but inserts newlines to wrap lines wider than `columns`
+    /// display columns.
+    #[inline]
+    pub(crate) const fn with_wrap(columns: usize) -> Self {
+        let mut result = Self::new();
+        result.wrap_columns = Some(columns);
+        result
+    }
+
+    /// Like `new`, but appends a newline when closing a stream which doesn't
+    /// end in one.
+    #[inline]
+    pub(crate) const fn with_auto_final_newline() -> Self {
+        let mut result = Self::new();
+        result.auto_final_newline = true;
+        result
+    }
+
     #[inline]
     pub(crate) fn with_bom_compatibility<Inner: WriteStr + WriteLayered>(
         inner: &mut Inner,
@@ -166,6 +264,24 @@ impl TextOutput {
         Ok(internals.into_inner())
     }
 
+    /// Flush the underlying stream and return the underlying stream object,
+    /// permitting the stream to end without a newline.
+    pub(crate) fn finish_without_newline<Inner: WriteStr + WriteLayered>(
+        mut internals: impl TextWriterInternals<Inner>,
+    ) -> io::Result<Inner> {
+        if let Err(err) = internals.impl_().check_flush() {
+            Self::prepare_failure(&mut internals);
+            return Err(err);
+        }
+        Self::write_wrap_pending(&mut internals)?;
+        if let Err(err) = internals.inner_mut().flush() {
+            Self::prepare_failure(&mut internals);
+            return Err(err);
+        }
+        Self::reset_state(&mut internals);
+        Ok(internals.into_inner())
+    }
+
     /// Discard and close the underlying stream and return the underlying
     /// stream object.
     pub(crate) fn abandon_into_inner<Inner: WriteStr + WriteLayered>(
@@ -176,38 +292,112 @@ impl TextOutput {
         internals.into_inner()
     }
 
-    fn normal_write_str<Inner: WriteStr + WriteLayered>(
-        internals: &mut impl TextWriterInternals<Inner>,
-        s: &str,
-    ) -> io::Result<()> {
-        Self::state_machine(internals, s)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        // Write to the underlying stream.
-        Self::write_buffer(internals)
+    /// Run `s` through the state machine, appending the output to the
+    /// temporary buffer.
+    ///
+    /// On failure, return the length of the prefix of `s` which the buffer
+    /// and state reflect, the offset in `s` at which the error was detected,
+    /// and the error.
+    fn process_str(&mut self, s: &str) -> Result<(), (usize, usize, BasicTextError)> {
+        if self.crlf_compatibility {
+            self.crlf_process_str(s)
+        } else {
+            self.state_machine(s)
+        }
     }
 
-    fn crlf_write_str<Inner: WriteStr + WriteLayered>(
-        internals: &mut impl TextWriterInternals<Inner>,
-        s: &str,
-    ) -> io::Result<()> {
+    fn crlf_process_str(&mut self, s: &str) -> Result<(), (usize, usize, BasicTextError)> {
         // Translate "\n" into "\r\n".
         let mut first = true;
+        let mut offset = 0;
         for slice in s.split('\n') {
             if first {
                 first = false;
             } else {
-                let impl_ = internals.impl_();
-                impl_.state = State::Ground(Ground::Newline);
-                impl_.buffer.push_str("\r\n");
+                self.state = State::Ground(Ground::Newline);
+                self.buffer.push_str("\r\n");
+                offset += 1;
             }
 
-            Self::state_machine(internals, slice)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.state_machine(slice)
+                .map_err(|(n, at, e)| (offset + n, offset + at, e))?;
+            offset += slice.len();
         }
 
-        // Write to the underlying stream.
-        Self::write_buffer(internals)
+        Ok(())
+    }
+
+    /// Report an error detected in a previous call to `stage`.
+    fn check_pending_error(&mut self) -> io::Result<()> {
+        match self.pending_error.take() {
+            Some((offset, error)) => {
+                self.error_offset = offset;
+                Err(io::Error::new(io::ErrorKind::InvalidData, error))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// When a `write` fails, return the offset in its buffer at which the
+    /// error was detected.
+    pub(crate) fn error_offset(&self) -> usize {
+        self.error_offset
+    }
+
+    /// Translate as much of `buf` as is valid into the temporary buffer, and
+    /// return the number of bytes consumed. If an error occurs after a valid
+    /// prefix, it's deferred to the next call.
+    pub(crate) fn stage(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.error_offset = 0;
+        self.check_pending_error()?;
+
+        let s = match str::from_utf8(buf) {
+            Ok(s) => s,
+            // Safety: See the example code here:
+            // https://doc.rust-lang.org/std/str/struct.Utf8Error.html#examples
+            Err(error) if error.valid_up_to() != 0 => unsafe {
+                str::from_utf8_unchecked(&buf[..error.valid_up_to()])
+            },
+            Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+        };
+
+        match self.process_str(s) {
+            Ok(()) => (),
+            Err((0, error_at, error)) => {
+                self.error_offset = error_at;
+                return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            Err((valid_up_to, error_at, error)) => {
+                self.pending_error = Some((error_at - valid_up_to, error));
+                return self.check_starter().map(|()| valid_up_to);
+            }
+        }
+
+        self.check_starter().map(|()| s.len())
+    }
+
+    /// Move the contents of the temporary buffer to the end of `out`.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn drain_staged(&mut self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.buffer.as_bytes());
+        self.buffer.clear();
+    }
+
+    /// If we're expecting a starter, check that the temporary buffer begins
+    /// with one.
+    fn check_starter(&mut self) -> io::Result<()> {
+        if self.expect_starter {
+            self.expect_starter = false;
+            if let Some(c) = self.buffer.chars().next() {
+                if !is_basic_text_start(c) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "write data must begin with a Unicode Normalization Form starter",
+                    ));
+                }
+            }
+        }
+        Ok(())
     }
 
     fn normal_write_text<Inner: WriteStr + WriteLayered>(
@@ -262,20 +452,17 @@ impl TextOutput {
     fn write_buffer<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
     ) -> io::Result<()> {
-        if internals.impl_().expect_starter {
-            internals.impl_().expect_starter = false;
-            if let Some(c) = internals.impl_().buffer.chars().next() {
-                if !is_basic_text_start(c) {
-                    Self::prepare_failure(internals);
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "write data must begin with a Unicode Normalization Form starter",
-                    ));
-                }
-            }
+        if let Err(err) = internals.impl_().check_starter() {
+            Self::prepare_failure(internals);
+            return Err(err);
         }
 
-        let buffer = take(&mut internals.impl_().buffer);
+        let mut buffer = take(&mut internals.impl_().buffer);
+        if internals.impl_().wrap_columns.is_some() {
+            let wrapped = internals.impl_().wrap(&buffer, false);
+            buffer.clear();
+            buffer.push_str(&wrapped);
+        }
         match internals.write_str(&buffer) {
             Ok(()) => (),
             Err(err) => {
@@ -283,6 +470,7 @@ impl TextOutput {
                 return Err(err);
             }
         }
+        internals.impl_().count_written(&buffer);
         internals.impl_().buffer = buffer;
 
         // Reset the temporary buffer.
@@ -291,90 +479,301 @@ impl TextOutput {
         Ok(())
     }
 
-    fn state_machine<Inner: WriteStr + WriteLayered>(
+    /// When wrapping, write out any output held back on the current line.
+    fn write_wrap_pending<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
-        s: &str,
-    ) -> Result<(), BasicTextError> {
+    ) -> io::Result<()> {
+        if internals.impl_().wrap_pending.is_empty() {
+            return Ok(());
+        }
+        let wrapped = internals.impl_().wrap("", true);
+        if let Err(err) = internals.write_str(&wrapped) {
+            Self::prepare_failure(internals);
+            return Err(err);
+        }
+        internals.impl_().count_written(&wrapped);
+        Ok(())
+    }
+
+    /// Update the counts of output written with `s`.
+    fn count_written(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+
+        self.stats.bytes += s.len() as u64;
+        self.stats.lines += s.bytes().filter(|b| *b == b'\n').count() as u64;
+
+        // Count the graphemes following the last one written, which `s` may
+        // extend.
+        if let Some(last_grapheme) = &mut self.last_grapheme {
+            let last_len = last_grapheme.len();
+            last_grapheme.push_str(s);
+            let mut last = 0;
+            for (i, _) in last_grapheme.grapheme_indices(true) {
+                if i >= last_len {
+                    self.stats.graphemes += 1;
+                }
+                last = i;
+            }
+            last_grapheme.drain(..last);
+        }
+    }
+
+    /// Start counting the grapheme clusters written.
+    pub(crate) fn count_graphemes(&mut self) {
+        if self.last_grapheme.is_none() {
+            self.last_grapheme = Some(String::new());
+        }
+    }
+
+    /// Append `s` to the held-back output on the current line, and return
+    /// the output which can be written, with newlines inserted to wrap lines
+    /// which are too wide. If `end` is true, return all the output, as no
+    /// more is coming until after a flush.
+    fn wrap(&mut self, s: &str, end: bool) -> String {
+        let mut pending = take(&mut self.wrap_pending);
+        pending.push_str(s);
+
+        let mut out = String::new();
+        let mut rest = pending.as_str();
+        while let Some(i) = rest.find('\n') {
+            let (line, after) = rest.split_at(i + 1);
+            self.wrap_line(line, true, &mut out);
+            rest = after;
+        }
+        let n = self.wrap_line(rest, end, &mut out);
+        self.wrap_pending = rest[n..].to_owned();
+
+        out
+    }
+
+    /// Append `line` to `out`, inserting newlines at grapheme cluster
+    /// boundaries, preferring whitespace, to keep it within the wrap width.
+    /// Return the number of bytes of `line` consumed. If `complete` is false,
+    /// the end of `line` is held back, as it may still be broken or extended.
+    fn wrap_line(&mut self, line: &str, complete: bool, out: &mut String) -> usize {
+        let columns = self.wrap_columns.unwrap();
+
+        let mut graphemes = line.grapheme_indices(true).collect::<Vec<_>>();
+        if !complete {
+            // The last grapheme cluster may be extended by later output.
+            graphemes.pop();
+        }
+
+        let mut start = 0;
+        let mut width = self.column;
+        let mut space: Option<(usize, usize)> = None;
+        for (i, g) in graphemes {
+            if g == "\n" {
+                break;
+            }
+            let w = g.width();
+            if g.chars().all(char::is_whitespace) {
+                // Extend a run of whitespace, or start a new one.
+                space = match space {
+                    Some((s, e)) if e == i => Some((s, i + g.len())),
+                    _ => Some((i, i + g.len())),
+                };
+            } else if width + w > columns && width != 0 {
+                match space {
+                    Some((s, e)) => {
+                        // Break at the most recent whitespace, dropping it.
+                        out.push_str(&line[start..s]);
+                        out.push('\n');
+                        self.column = 0;
+                        width = line[e..i].width();
+                        start = e;
+                    }
+                    None if self.column != 0 && line[start..].starts_with(is_basic_text_start) => {
+                        // Break after the output written before a flush.
+                        out.push('\n');
+                        self.column = 0;
+                        width = line[start..i].width();
+                    }
+                    None if i != start => {
+                        // No whitespace; break between grapheme clusters.
+                        out.push_str(&line[start..i]);
+                        out.push('\n');
+                        self.column = 0;
+                        width = 0;
+                        start = i;
+                    }
+                    None => (),
+                }
+                space = None;
+            }
+            width += w;
+        }
+
+        if complete {
+            out.push_str(&line[start..]);
+            self.column = if line.ends_with('\n') { 0 } else { width };
+            line.len()
+        } else {
+            start
+        }
+    }
+
+    fn state_machine(&mut self, s: &str) -> Result<(), (usize, usize, BasicTextError)> {
         let error = Rc::new(RefCell::new(None));
 
         if is_nfc_stream_safe_quick(s.chars()) == IsNormalized::Yes
             && s.chars().all(is_public_assigned)
         {
             // Fast path: Data is already Stream-Safe NFC and assigned. Just
-            // check for errors.
-            for c in s.chars().categorize(Rc::clone(&error)) {
-                Self::state_machine_char(internals, c, &error)?;
+            // check for errors. `categorize` maps each input scalar value to
+            // one output scalar value, so on failure, the buffer and state
+            // reflect exactly the input before the offending scalar value.
+            for ((i, _), c) in s
+                .char_indices()
+                .zip(s.chars().categorize(Rc::clone(&error)))
+            {
+                self.state_machine_char(c, &error).map_err(|e| (i, i, e))?;
             }
         } else {
-            Self::state_machine_slow_path(internals, s, &error)?;
+            self.state_machine_slow_path(s, &error)?;
         }
 
         Ok(())
     }
 
     #[cold]
-    fn state_machine_slow_path<Inner: WriteStr + WriteLayered>(
-        internals: &mut impl TextWriterInternals<Inner>,
+    fn state_machine_slow_path(
+        &mut self,
         s: &str,
         error: &Rc<RefCell<Option<BasicTextError>>>,
-    ) -> Result<(), BasicTextError> {
+    ) -> Result<(), (usize, usize, BasicTextError)> {
+        // Normalization can reorder and compose scalar values, so output
+        // positions don't map precisely onto input positions. Newlines are
+        // normalization boundaries though, so remember the most recent one,
+        // and on failure, roll back to it.
+        let mut newlines = s.match_indices('\n').map(|(i, _)| i + 1);
+        let mut valid_up_to = 0;
+        let mut valid_buffer_len = self.buffer.len();
+        let mut valid_state = self.state;
+
         // Slow path: Compute Stream-Safe NFC, isolate unassigned scalar
         // values, and check for errors.
-        for c in s
-            .chars()
-            .categorize(Rc::clone(error))
-            .isolate_unassigned()
-            .cjk_compat_variants()
-            .stream_safe()
-            .nfc()
-        {
-            Self::state_machine_char(internals, c, error)?;
+        for c in StreamSafeNfc::new(s.chars().categorize(Rc::clone(error))) {
+            if let Err(e) = self.state_machine_char(c, error) {
+                self.buffer.truncate(valid_buffer_len);
+                self.state = valid_state;
+                let error_at = valid_up_to + self.locate_error(&s[valid_up_to..]);
+                return Err((valid_up_to, error_at, e));
+            }
+
+            if c == '\n' {
+                valid_up_to = newlines.next().unwrap();
+                valid_buffer_len = self.buffer.len();
+                valid_state = self.state;
+            }
         }
 
         Ok(())
     }
 
-    fn state_machine_char<Inner: WriteStr + WriteLayered>(
-        internals: &mut impl TextWriterInternals<Inner>,
+    /// Find the offset in `s` of the scalar value at which the slow path
+    /// fails, by searching for the shortest failing prefix, leaving the
+    /// buffer and state unchanged.
+    #[cold]
+    fn locate_error(&mut self, s: &str) -> usize {
+        let state = self.state;
+        let buffer_len = self.buffer.len();
+        let escape_sequence = self.escape_sequence.clone();
+
+        let ends = s
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .collect::<Vec<_>>();
+        let n = ends.partition_point(|end| {
+            let error = Rc::new(RefCell::new(None));
+            let failed = StreamSafeNfc::new(s[..*end].chars().categorize(Rc::clone(&error)))
+                .any(|c| self.state_machine_char(c, &error).is_err());
+            self.buffer.truncate(buffer_len);
+            self.state = state;
+            self.escape_sequence.clone_from(&escape_sequence);
+            !failed
+        });
+
+        // The failing scalar value is the last one in the failing prefix.
+        match n {
+            0 => 0,
+            n => ends[n - 1],
+        }
+    }
+
+    fn state_machine_char(
+        &mut self,
         c: char,
         error: &Rc<RefCell<Option<BasicTextError>>>,
     ) -> Result<(), BasicTextError> {
-        let impl_ = internals.impl_();
-        match (&impl_.state, c) {
+        match (&self.state, c) {
+            // A page break must be immediately followed by a newline.
+            (State::Ground(Ground::FormFeed), c) if c != '\n' => {
+                return Err(BasicTextError::ControlCode(FF));
+            }
+
             // Recognize ANSI-style color escape sequences.
-            (State::Ground(_), ESC) if impl_.ansi_color => {
-                impl_.state = State::Esc;
-                impl_.escape_sequence.clear();
-                impl_.escape_sequence.push(ESC);
+            (State::Ground(_), ESC) if self.ansi_color => {
+                self.state = State::Esc;
+                self.escape_sequence.clear();
+                self.escape_sequence.push(ESC);
             }
             (State::Esc, '[') => {
-                impl_.state = State::Csi;
-                impl_.escape_sequence.push('[');
+                self.state = State::Csi;
+                self.escape_sequence.push('[');
             }
-            (State::Csi, c) if matches!(c, ' '..='?') => impl_.escape_sequence.push(c),
+            (State::Csi, c) if matches!(c, ' '..='?') => self.escape_sequence.push(c),
             (State::Csi, 'm') => {
-                impl_.escape_sequence.push('m');
-                impl_.buffer.push_str(&impl_.escape_sequence);
-                impl_.state = State::Ground(Ground::Other);
+                self.escape_sequence.push('m');
+                self.buffer.push_str(&self.escape_sequence);
+                self.state = State::Ground(Ground::Other);
             }
 
             (State::Ground(_), '\n') => {
-                impl_.state = State::Ground(Ground::Newline);
-                impl_.buffer.push(c);
+                self.state = State::Ground(Ground::Newline);
+                self.buffer.push(c);
+            }
+
+            // In form-feed compatibility mode, permit a U+C (FF) at the
+            // beginning of a line as a page break.
+            (State::Ground(Ground::Newline), SUB)
+                if self.form_feed_compatibility
+                    && matches!(*error.borrow(), Some(BasicTextError::ControlCode(FF))) =>
+            {
+                *error.borrow_mut() = None;
+                self.state = State::Ground(Ground::FormFeed);
+                self.buffer.push(FF);
+            }
+
+            // Permit control codes which have been explicitly allowed.
+            (State::Ground(_), SUB)
+                if matches!(
+                    *error.borrow(),
+                    Some(BasicTextError::ControlCode(c)) if self.allowed_controls.contains(&c)
+                ) =>
+            {
+                let c = match take(&mut *error.borrow_mut()) {
+                    Some(BasicTextError::ControlCode(c)) => c,
+                    _ => unreachable!(),
+                };
+                self.state = State::Ground(Ground::Other);
+                self.buffer.push(c);
+            }
+            (State::Ground(_), ESC) if self.allowed_controls.contains(&ESC) => {
+                self.state = State::Ground(Ground::Other);
+                self.buffer.push(ESC);
             }
 
             (State::Ground(_), SUB) => {
                 // SUB indicates an error sent through the NFC iterator
                 // chain, and the Rc<RefCell<Option<BasicTextError>>> holds the
                 // actual error.
-                Self::prepare_failure(internals);
                 return Err(take(&mut *error.borrow_mut()).unwrap());
             }
 
-            (State::Ground(_), ESC) => {
-                Self::prepare_failure(internals);
-                return Err(BasicTextError::Escape);
-            }
+            (State::Ground(_), ESC) => return Err(BasicTextError::Escape),
 
             // Common case: in ground state and reading a normal char.
             (State::Ground(_), c) => {
@@ -383,15 +782,12 @@ impl TextOutput {
                 } else {
                     Ground::Other
                 };
-                impl_.state = State::Ground(ground);
-                impl_.buffer.push(c);
+                self.state = State::Ground(ground);
+                self.buffer.push(c);
             }
 
             // Escape sequence not recognized.
-            (State::Esc, _) | (State::Csi, _) => {
-                Self::prepare_failure(internals);
-                return Err(BasicTextError::UnrecognizedEscape);
-            }
+            (State::Esc, _) | (State::Csi, _) => return Err(BasicTextError::UnrecognizedEscape),
         }
         Ok(())
     }
@@ -399,30 +795,64 @@ impl TextOutput {
     fn check_nl<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
     ) -> io::Result<()> {
-        match internals.impl_().state {
+        // A missing newline can be appended, but a stream ending after a ZWJ,
+        // Prepend, or in an escape sequence can't be fixed by appending one.
+        let impl_ = internals.impl_();
+        if impl_.auto_final_newline
+            && matches!(
+                impl_.state,
+                State::Ground(Ground::Other) | State::Ground(Ground::FormFeed)
+            )
+        {
+            Self::write_str(internals, "\n")?;
+        }
+
+        if let Err(err) = internals.impl_().check_end() {
+            Self::prepare_failure(internals);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Check that the stream may end in its current state.
+    pub(crate) fn check_end(&self) -> io::Result<()> {
+        match self.state {
             State::Ground(Ground::Newline) => Ok(()),
+            State::Ground(Ground::ZwjOrPrepend) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "strict text stream ended after a ZWJ or Prepend",
+            )),
+            State::Ground(Ground::Other) | State::Ground(Ground::FormFeed) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "strict text stream must end with newline",
+            )),
+            State::Esc | State::Csi => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete escape sequence at end of strict text stream",
+            )),
+        }
+    }
+
+    /// Check that the stream may be flushed in its current state, and if so,
+    /// expect the data after the flush to begin with a starter.
+    pub(crate) fn check_flush(&mut self) -> io::Result<()> {
+        match self.state {
             State::Ground(Ground::ZwjOrPrepend) => {
-                Self::prepare_failure(internals);
-                Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "strict text stream ended after a ZWJ or Prepend",
-                ))
-            }
-            State::Ground(Ground::Other) => {
-                Self::prepare_failure(internals);
-                Err(io::Error::new(
+                return Err(io::Error::new(
                     io::ErrorKind::InvalidData,
-                    "strict text stream must end with newline",
+                    "strict text stream flushed after a ZWJ or Prepend",
                 ))
             }
+            State::Ground(_) => (),
             State::Esc | State::Csi => {
-                Self::prepare_failure(internals);
-                Err(io::Error::new(
+                return Err(io::Error::new(
                     io::ErrorKind::InvalidData,
-                    "incomplete escape sequence at end of strict text stream",
+                    "strict text stream flushed while an escape sequence was in progress",
                 ))
             }
         }
+        self.expect_starter = true;
+        Ok(())
     }
 
     pub(crate) fn close<Inner: WriteStr + WriteLayered>(
@@ -433,6 +863,21 @@ impl TextOutput {
         internals.inner_mut().close()
     }
 
+    /// Check that the current document may end, flush it, and restore the
+    /// initial state so that a new document can be written.
+    pub(crate) fn finish_document<Inner: WriteStr + WriteLayered>(
+        internals: &mut impl TextWriterInternals<Inner>,
+    ) -> io::Result<()> {
+        Self::check_nl(internals)?;
+        Self::write_wrap_pending(internals)?;
+        if let Err(err) = internals.inner_mut().flush() {
+            Self::prepare_failure(internals);
+            return Err(err);
+        }
+        internals.impl_().restart();
+        Ok(())
+    }
+
     pub(crate) fn abandon<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
     ) {
@@ -447,10 +892,57 @@ impl TextOutput {
         internals.inner().suggested_buffer_size()
     }
 
+    pub(crate) fn write_text<Inner: WriteStr + WriteLayered>(
+        internals: &mut impl TextWriterInternals<Inner>,
+        s: &TextStr,
+    ) -> io::Result<()> {
+        let impl_ = internals.impl_();
+        if s.is_empty()
+            || impl_.crlf_compatibility
+            || impl_.wrap_columns.is_some()
+            || !impl_.buffer.is_empty()
+            || impl_.pending_error.is_some()
+            || !matches!(impl_.state, State::Ground(Ground::Newline | Ground::Other))
+        {
+            return Self::write_text_substr(internals, s.as_ref());
+        }
+
+        // A `TextStr` always begins with a starter and can always end a
+        // stream, so we can skip `check_starter` and write it directly,
+        // rather than copying it into the temporary buffer.
+        impl_.expect_starter = false;
+        impl_.state = State::Ground(if s.as_str().ends_with('\n') {
+            Ground::Newline
+        } else {
+            Ground::Other
+        });
+
+        if let Err(err) = internals.write_str(s.as_str()) {
+            Self::prepare_failure(internals);
+            return Err(err);
+        }
+        internals.impl_().count_written(s.as_str());
+        Ok(())
+    }
+
     pub(crate) fn write_text_substr<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
         s: &TextSubstr,
     ) -> io::Result<()> {
+        if let Err(err) = internals.impl_().check_pending_error() {
+            Self::prepare_failure(internals);
+            return Err(err);
+        }
+
+        // A page break or escape sequence in progress constrains what may
+        // follow, so use the full state machine.
+        if !matches!(
+            internals.impl_().state,
+            State::Ground(Ground::Newline | Ground::ZwjOrPrepend | Ground::Other)
+        ) {
+            return Self::write_str(internals, s.as_ref());
+        }
+
         if internals.impl_().crlf_compatibility {
             Self::crlf_write_text(internals, s)
         } else {
@@ -462,28 +954,30 @@ impl TextOutput {
         internals: &mut impl TextWriterInternals<Inner>,
         s: &str,
     ) -> io::Result<()> {
-        if internals.impl_().crlf_compatibility {
-            Self::crlf_write_str(internals, s)
-        } else {
-            Self::normal_write_str(internals, s)
+        if let Err(err) = internals.impl_().check_pending_error() {
+            Self::prepare_failure(internals);
+            return Err(err);
         }
+
+        if let Err((_, _, error)) = internals.impl_().process_str(s) {
+            Self::prepare_failure(internals);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+
+        // Write to the underlying stream.
+        Self::write_buffer(internals)
     }
 
     pub(crate) fn write<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
         buf: &[u8],
     ) -> io::Result<usize> {
-        match str::from_utf8(buf) {
-            Ok(s) => Self::write_str(internals, s).map(|()| buf.len()),
-            // Safety: See the example code here:
-            // https://doc.rust-lang.org/std/str/struct.Utf8Error.html#examples
-            Err(error) if error.valid_up_to() != 0 => Self::write_str(internals, unsafe {
-                str::from_utf8_unchecked(&buf[..error.valid_up_to()])
-            })
-            .map(|()| error.valid_up_to()),
-            Err(error) => {
+        match internals.impl_().stage(buf) {
+            // Write to the underlying stream.
+            Ok(n) => Self::write_buffer(internals).map(|()| n),
+            Err(err) => {
                 Self::prepare_failure(internals);
-                Err(io::Error::new(io::ErrorKind::InvalidData, error))
+                Err(err)
             }
         }
     }
@@ -492,23 +986,16 @@ impl TextOutput {
     pub(crate) fn flush<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
     ) -> io::Result<()> {
-        match internals.impl_().state {
-            State::Ground(Ground::ZwjOrPrepend) => {
+        if let Err(err) = internals.impl_().check_flush() {
+            // An escape sequence may still be completed, so only a ZWJ or
+            // Prepend is fatal.
+            if let State::Ground(_) = internals.impl_().state {
                 Self::prepare_failure(internals);
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "strict text stream flushed after a ZWJ or Prepend",
-                ));
-            }
-            State::Ground(_) => (),
-            State::Esc | State::Csi => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "strict text stream flushed while an escape sequence was in progress",
-                ))
             }
+            internals.impl_().poisoned = true;
+            return Err(err);
         }
-        internals.impl_().expect_starter = true;
+        Self::write_wrap_pending(internals)?;
         internals.inner_mut().flush()
     }
 
@@ -550,8 +1037,41 @@ impl TextOutput {
     fn reset_state<Inner: WriteStr + WriteLayered>(
         internals: &mut impl TextWriterInternals<Inner>,
     ) {
-        // Don't enforce a trailing newline.
-        internals.impl_().state = State::Ground(Ground::Newline);
+        internals.impl_().reset();
+    }
+
+    /// Restore the initial state, so that a new document can be written,
+    /// keeping the configuration options.
+    fn restart(&mut self) {
+        self.buffer.clear();
+        self.expect_starter = true;
+        self.state = State::Ground(Ground::Newline);
+        self.escape_sequence.clear();
+        self.pending_error = None;
+        self.poisoned = false;
+        self.column = 0;
+        self.wrap_pending.clear();
+        if let Some(last_grapheme) = &mut self.last_grapheme {
+            last_grapheme.clear();
+        }
+    }
+
+    /// Test whether dropping this `TextOutput` will neither panic nor skip
+    /// its check, meaning the stream hasn't failed, we aren't unwinding,
+    /// and the stream ends with a newline.
+    pub(crate) fn drops_cleanly(&self) -> bool {
+        !self.poisoned && !thread::panicking() && self.at_newline()
+    }
+
+    fn at_newline(&self) -> bool {
+        matches!(self.state, State::Ground(Ground::Newline))
+    }
+
+    /// Reset the state so that a trailing newline isn't enforced.
+    pub(crate) fn reset(&mut self) {
+        self.state = State::Ground(Ground::Newline);
+        self.wrap_pending.clear();
+        self.poisoned = true;
     }
 
     fn prepare_failure<Inner: WriteStr + WriteLayered>(
@@ -564,23 +1084,30 @@ impl TextOutput {
 
 impl Drop for TextOutput {
     fn drop(&mut self) {
-        if let State::Ground(Ground::Newline) = self.state {
-            // oll korrect
-        } else {
+        // After an error, or while unwinding, don't pile on another panic.
+        if self.poisoned || thread::panicking() {
+            return;
+        }
+
+        if !self.at_newline() {
             panic!("strict text stream not ended with newline");
         }
     }
 }
 
+#[derive(Clone, Copy)]
 enum Ground {
     // We just saw a '\n'.
     Newline,
     // We just saw a ZWJ or a Prepend.
     ZwjOrPrepend,
+    // We just saw a U+C (FF) page break.
+    FormFeed,
     // Otherwise.
     Other,
 }
 
+#[derive(Clone, Copy)]
 enum State {
     // Default state.
     Ground(Ground),
@@ -1,12 +1,18 @@
 use crate::text_input::TextInput;
-use crate::{ReadText, ReadTextLayered, TextSubstr};
+use crate::{
+    LineBreakPolicy, LineEnding, ReadText, ReadTextLayered, RepairStats, TabPolicy, TextString,
+    TextSubstr, TextSubstring,
+};
+use basic_text_internals::unicode::NORMALIZATION_BUFFER_SIZE;
 #[cfg(windows)]
 use io_extras::os::windows::{
     AsHandleOrSocket, AsRawHandleOrSocket, BorrowedHandleOrSocket, RawHandleOrSocket,
 };
 use layered_io::{default_read_to_end, Bufferable, LayeredReader, ReadLayered, Status};
+use std::cmp::min;
 use std::fmt::{self, Debug, Formatter};
 use std::io::{self, Read};
+use std::iter::Fuse;
 use std::str;
 #[cfg(feature = "terminal-io")]
 use terminal_io::{ReadTerminal, Terminal};
@@ -58,6 +64,134 @@ impl<Inner: Read> TextReader<Utf8Reader<LayeredReader<Inner>>> {
     pub fn with_lsps_compatibility(inner: Inner) -> io::Result<Self> {
         Self::from_utf8_with_lsps_compatibility(Utf8Reader::new(LayeredReader::new(inner)))
     }
+
+    /// Like `new`, but translates the scalar values selected by `policy` into
+    /// U+A instead of U+20.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::{LineBreakPolicy, TextReader};
+    /// use std::io::Read;
+    ///
+    /// let mut reader = TextReader::with_line_break_policy(
+    ///     "a\u{85}b\u{2028}c".as_bytes(),
+    ///     LineBreakPolicy::NEL,
+    /// );
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "a\nb c\n");
+    /// ```
+    #[inline]
+    pub fn with_line_break_policy(inner: Inner, policy: LineBreakPolicy) -> Self {
+        Self::from_utf8_with_line_break_policy(Utf8Reader::new(LayeredReader::new(inner)), policy)
+    }
+
+    /// Like `new`, but handles U+9 (tab) according to `policy`, instead of
+    /// passing it through unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::{TabPolicy, TextReader};
+    /// use std::io::Read;
+    ///
+    /// let mut reader = TextReader::with_tab_policy("\tx\nab\ty".as_bytes(), TabPolicy::ExpandTo(4));
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "    x\nab  y\n");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `policy` is `TabPolicy::ExpandTo(0)`.
+    #[inline]
+    pub fn with_tab_policy(inner: Inner, policy: TabPolicy) -> Self {
+        Self::from_utf8_with_tab_policy(Utf8Reader::new(LayeredReader::new(inner)), policy)
+    }
+
+    /// Like `new`, but preserves U+C (FF) as a page break instead of
+    /// replacing it with U+20.
+    ///
+    /// Each U+C is emitted on a line by itself, as expected by
+    /// [`TextWriter::with_form_feed_compatibility`], with newlines inserted
+    /// before and after it as needed. These inserted newlines are not
+    /// counted as repairs.
+    ///
+    /// Since U+C isn't Basic Text, the output in this mode may only be read
+    /// as bytes or as a `str`; the `ReadText` methods, `read_line_text`, and
+    /// `read_exact_graphemes` fail with an error.
+    ///
+    /// [`TextWriter::with_form_feed_compatibility`]: crate::TextWriter::with_form_feed_compatibility
+    #[inline]
+    pub fn with_form_feed_compatibility(inner: Inner) -> io::Result<Self> {
+        Self::from_utf8_with_form_feed_compatibility(Utf8Reader::new(LayeredReader::new(inner)))
+    }
+
+    /// Like `new`, but treats a leading U+FEFF (BOM) as content, replacing it
+    /// with U+2060 (WJ) like any other U+FEFF, instead of removing it.
+    #[inline]
+    pub fn with_bom_as_content(inner: Inner) -> Self {
+        Self::from_utf8_with_bom_as_content(Utf8Reader::new(LayeredReader::new(inner)))
+    }
+
+    /// Like `new`, but fails with an error if more than `limit` bytes of
+    /// input are read without a line boundary, rather than buffering them
+    /// without bound.
+    #[inline]
+    pub fn with_max_buffer(inner: Inner, limit: usize) -> Self {
+        Self::from_utf8_with_max_buffer(Utf8Reader::new(LayeredReader::new(inner)), limit)
+    }
+}
+
+impl<I: Iterator<Item = u8>> TextReader<Utf8Reader<LayeredReader<IterReader<I>>>> {
+    /// Construct a new instance of `TextReader` reading from `iter`, which
+    /// can be anything that produces bytes, such as a decompressor, without
+    /// needing to implement [`Read`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextReader;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = TextReader::from_iter(b"hello\r\nworld".iter().copied());
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "hello\nworld\n");
+    /// ```
+    // This can't be a `FromIterator` impl, because the type of the reader
+    // depends on the type of the iterator.
+    #[allow(clippy::should_implement_trait)]
+    #[inline]
+    pub fn from_iter<T: IntoIterator<IntoIter = I>>(iter: T) -> Self {
+        Self::new(IterReader::new(iter.into_iter()))
+    }
+}
+
+impl<Inner: Read> TextReader<Utf8Reader<LayeredReader<Windows1252Reader<Inner>>>> {
+    /// Construct a new instance of `TextReader` wrapping `inner`, which
+    /// produces bytes in the Windows-1252 encoding rather than UTF-8.
+    ///
+    /// This also reads ISO-8859-1 (Latin-1), in the same way web browsers
+    /// do, since the encodings differ only in U+80–U+9F, which are C1
+    /// control codes in ISO-8859-1 and which Basic Text excludes anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextReader;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = TextReader::from_windows_1252(b"caf\xe9 \x93hi\x94\r\n".as_slice());
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "caf\u{e9} \u{201c}hi\u{201d}\n");
+    /// ```
+    #[inline]
+    pub fn from_windows_1252(inner: Inner) -> Self {
+        Self::new(Windows1252Reader::new(inner))
+    }
 }
 
 impl<Inner: ReadStrLayered> TextReader<Inner> {
@@ -75,17 +209,280 @@ impl<Inner: ReadStrLayered> TextReader<Inner> {
     /// Like `from_utf8`, but replaces U+85 (NEL) with U+A instead of U+20.
     #[inline]
     pub fn from_utf8_with_nel_compatibility(inner: Inner) -> io::Result<Self> {
-        let input = TextInput::with_nel_compatibility();
-        Ok(Self { inner, input })
+        Ok(Self::from_utf8_with_line_break_policy(
+            inner,
+            LineBreakPolicy::NEL,
+        ))
     }
 
     /// Like `from_utf8`, but replaces U+2028 (LS) and U+2029 (PS) with U+A
     /// instead of U+20.
     #[inline]
     pub fn from_utf8_with_lsps_compatibility(inner: Inner) -> io::Result<Self> {
-        let input = TextInput::with_lsps_compatibility();
+        Ok(Self::from_utf8_with_line_break_policy(
+            inner,
+            LineBreakPolicy::LS | LineBreakPolicy::PS,
+        ))
+    }
+
+    /// Like `from_utf8`, but translates the scalar values selected by `policy`
+    /// into U+A instead of U+20.
+    #[inline]
+    pub fn from_utf8_with_line_break_policy(inner: Inner, policy: LineBreakPolicy) -> Self {
+        Self {
+            inner,
+            input: TextInput::with_line_break_policy(policy),
+        }
+    }
+
+    /// Like `from_utf8`, but handles U+9 (tab) according to `policy`,
+    /// instead of passing it through unchanged.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `policy` is `TabPolicy::ExpandTo(0)`.
+    #[inline]
+    pub fn from_utf8_with_tab_policy(inner: Inner, policy: TabPolicy) -> Self {
+        Self {
+            inner,
+            input: TextInput::with_tab_policy(policy),
+        }
+    }
+
+    /// Like `from_utf8`, but preserves U+C (FF) as a page break instead of
+    /// replacing it with U+20.
+    ///
+    /// Each U+C is emitted on a line by itself, as expected by
+    /// [`TextWriter::from_utf8_with_form_feed_compatibility`], with newlines
+    /// inserted before and after it as needed. These inserted newlines are
+    /// not counted as repairs.
+    ///
+    /// Since U+C isn't Basic Text, the output in this mode may only be read
+    /// as bytes or as a `str`; the `ReadText` methods, `read_line_text`, and
+    /// `read_exact_graphemes` fail with an error.
+    ///
+    /// [`TextWriter::from_utf8_with_form_feed_compatibility`]: crate::TextWriter::from_utf8_with_form_feed_compatibility
+    #[inline]
+    pub fn from_utf8_with_form_feed_compatibility(inner: Inner) -> io::Result<Self> {
+        let input = TextInput::with_form_feed_compatibility();
         Ok(Self { inner, input })
     }
+
+    /// Like `from_utf8`, but treats a leading U+FEFF (BOM) as content,
+    /// replacing it with U+2060 (WJ) like any other U+FEFF, instead of
+    /// removing it.
+    #[inline]
+    pub fn from_utf8_with_bom_as_content(inner: Inner) -> Self {
+        Self {
+            inner,
+            input: TextInput::with_bom_as_content(),
+        }
+    }
+
+    /// Like `from_utf8`, but fails with an error if more than `limit` bytes
+    /// of input are read without a line boundary, rather than buffering them
+    /// without bound.
+    ///
+    /// This bounds the input buffered by [`read_line_text`] too.
+    ///
+    /// [`read_line_text`]: Self::read_line_text
+    #[inline]
+    pub fn from_utf8_with_max_buffer(inner: Inner, limit: usize) -> Self {
+        Self {
+            inner,
+            input: TextInput::with_max_buffer(limit),
+        }
+    }
+
+    /// The minimum size of a buffer passed to [`read_with_status`] and
+    /// [`read_text_substr_with_status`], so that any valid normalized
+    /// sequence can be read.
+    ///
+    /// [`read_with_status`]: ReadLayered::read_with_status
+    /// [`read_text_substr_with_status`]: ReadTextLayered::read_text_substr_with_status
+    pub const MIN_BUFFER_SIZE: usize = NORMALIZATION_BUFFER_SIZE;
+
+    /// Allocate a zero-filled buffer of [`MIN_BUFFER_SIZE`] bytes, suitable
+    /// for passing to [`read_with_status`].
+    ///
+    /// [`MIN_BUFFER_SIZE`]: Self::MIN_BUFFER_SIZE
+    /// [`read_with_status`]: ReadLayered::read_with_status
+    #[inline]
+    pub fn recommended_buffer() -> Vec<u8> {
+        vec![0; Self::MIN_BUFFER_SIZE]
+    }
+
+    /// Return the counts of the repairs performed on the input so far.
+    #[inline]
+    pub fn repair_stats(&self) -> RepairStats {
+        self.input.stats
+    }
+
+    /// Return a reference to the underlying stream object.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the underlying stream object.
+    ///
+    /// Input may have been read from the underlying stream and buffered in
+    /// this `TextReader` without being returned yet. Reading from the
+    /// underlying stream directly skips over that input and can leave this
+    /// `TextReader` in the middle of an escape sequence or a grapheme
+    /// cluster, so subsequent output may be translated differently.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Test whether a leading U+FEFF (BOM) was removed from the input.
+    ///
+    /// This is only meaningful after the first read.
+    #[inline]
+    pub fn had_bom(&self) -> bool {
+        self.input.had_bom
+    }
+
+    /// Return the convention for line endings used by the input read so
+    /// far, before they were translated into U+A, or `None` if no line
+    /// endings have been read.
+    ///
+    /// A newline appended at the end of the stream doesn't count. When the
+    /// result is [`LineEnding::CrLf`], output can be written with the same
+    /// convention using [`TextWriter::with_crlf_compatibility`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::{LineEnding, TextReader};
+    /// use std::io::Read;
+    ///
+    /// let mut reader = TextReader::new(b"hello\r\nworld\r\n".as_slice());
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "hello\nworld\n");
+    /// assert_eq!(reader.detected_line_ending(), Some(LineEnding::CrLf));
+    /// ```
+    ///
+    /// [`TextWriter::with_crlf_compatibility`]: crate::TextWriter::with_crlf_compatibility
+    #[inline]
+    pub fn detected_line_ending(&self) -> Option<LineEnding> {
+        self.input.line_ending
+    }
+
+    /// Start capturing the escape sequences removed from the input, so that
+    /// they can be retrieved with [`take_escape_sequences`].
+    ///
+    /// Escape sequences are only captured once they're read, which may be
+    /// ahead of the output consumed so far.
+    ///
+    /// [`take_escape_sequences`]: Self::take_escape_sequences
+    #[inline]
+    pub fn capture_escape_sequences(&mut self) {
+        self.input.escape_sequences.get_or_insert_with(Vec::new);
+    }
+
+    /// Return the escape sequences captured since the last call, including
+    /// their leading U+1B (ESC).
+    ///
+    /// A sequence which is still incomplete at the time of the call is
+    /// returned as-is, and the rest of it is not captured.
+    #[inline]
+    pub fn take_escape_sequences(&mut self) -> Vec<String> {
+        self.input
+            .escape_sequences
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Start capturing the input byte offset of each scalar value output,
+    /// so that they can be retrieved with [`take_source_offsets`].
+    ///
+    /// Offsets are relative to the start of the input as decoded by the
+    /// underlying UTF-8 reader, so they differ from raw byte offsets after
+    /// invalid UTF-8, which is replaced by U+FFFD.
+    ///
+    /// [`take_source_offsets`]: Self::take_source_offsets
+    #[inline]
+    pub fn capture_source_offsets(&mut self) {
+        self.input.capture_source_offsets();
+    }
+
+    /// Return the input byte offsets captured since the last call, one for
+    /// each scalar value output, in output order.
+    ///
+    /// Scalar values translated from an input sequence, such as a U+A from a
+    /// U+D U+A (CRLF), are attributed to the start of that sequence, and
+    /// scalar values inserted at the end of the stream are attributed to the
+    /// end of the input. Normalization can compose, decompose, and reorder
+    /// scalar values, so scalar values produced by it are attributed to the
+    /// start of the sequence they were normalized with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextReader;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = TextReader::new("\u{feff}a\r\nb".as_bytes());
+    /// reader.capture_source_offsets();
+    /// let mut s = String::new();
+    /// reader.read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "a\nb\n");
+    /// assert_eq!(reader.take_source_offsets(), [3, 4, 6, 7]);
+    /// ```
+    #[inline]
+    pub fn take_source_offsets(&mut self) -> Vec<usize> {
+        self.input
+            .source_offsets
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Read a line of Basic Text, up to and including the next newline, and
+    /// append it to `buf`. Return the number of bytes appended, which is
+    /// zero at the end of the stream.
+    ///
+    /// Input after the newline stays buffered for subsequent reads. If an
+    /// error occurs, the partial line read so far is discarded.
+    ///
+    /// If the line starts with a non-starter and `buf` doesn't end with a
+    /// newline, such as when `buf` is empty, a U+34F (CGJ) is inserted
+    /// before it, so that `buf` remains valid Basic Text.
+    #[inline]
+    pub fn read_line_text(&mut self, buf: &mut TextString) -> io::Result<usize> {
+        TextInput::read_line_text(self, buf)
+    }
+
+    /// Read exactly `n` extended grapheme clusters of Basic Text, and append
+    /// them to `buf`.
+    ///
+    /// Unlike [`read_exact_text_substr`], this counts grapheme clusters
+    /// rather than bytes, which is useful for parsing fixed-width records.
+    /// Input after the last grapheme cluster stays buffered for subsequent
+    /// reads. If the stream ends before `n` grapheme clusters are read, this
+    /// fails with [`io::ErrorKind::UnexpectedEof`] and the partial input read
+    /// is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::{TextReader, TextSubstring};
+    ///
+    /// let mut reader = TextReader::new("A\u{30a}bc\r\n".as_bytes());
+    /// let mut s = TextSubstring::new();
+    /// reader.read_exact_graphemes(2, &mut s).unwrap();
+    /// assert_eq!(s.as_str(), "\u{c5}b");
+    /// ```
+    ///
+    /// [`read_exact_text_substr`]: ReadText::read_exact_text_substr
+    #[inline]
+    pub fn read_exact_graphemes(&mut self, n: usize, buf: &mut TextSubstring) -> io::Result<()> {
+        TextInput::read_exact_graphemes(self, n, buf)
+    }
 }
 
 #[cfg(feature = "terminal-io")]
@@ -162,6 +559,11 @@ impl<Inner: ReadStrLayered> ReadText for TextReader<Inner> {
     fn read_exact_text_substr(&mut self, buf: &mut TextSubstr) -> io::Result<()> {
         TextInput::read_exact_text_substr(self, buf)
     }
+
+    #[inline]
+    fn read_to_text_string(&mut self, buf: &mut TextString) -> io::Result<usize> {
+        TextInput::read_to_text_string(self, buf)
+    }
 }
 
 impl<Inner: ReadStrLayered> ReadTextLayered for TextReader<Inner> {
@@ -196,6 +598,127 @@ impl<Inner: ReadStrLayered> Read for TextReader<Inner> {
     }
 }
 
+/// A [`Read`] implementation which reads bytes from an iterator.
+///
+/// This is used by [`TextReader::from_iter`].
+pub struct IterReader<I: Iterator<Item = u8>> {
+    iter: Fuse<I>,
+}
+
+impl<I: Iterator<Item = u8>> IterReader<I> {
+    /// Construct a new instance of `IterReader` reading from `iter`.
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: Iterator::fuse(iter),
+        }
+    }
+}
+
+impl<I: Iterator<Item = u8>> Read for IterReader<I> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut n = 0;
+        for slot in buf.iter_mut() {
+            match self.iter.next() {
+                Some(b) => *slot = b,
+                None => break,
+            }
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+impl<I: Iterator<Item = u8>> Debug for IterReader<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IterReader").finish()
+    }
+}
+
+/// A [`Read`] implementation which decodes bytes in the Windows-1252
+/// encoding from an input `Read` implementation and produces UTF-8.
+///
+/// Bytes which Windows-1252 leaves undefined are decoded as the C1 control
+/// codes with the same values, as the WHATWG Encoding Standard specifies.
+///
+/// This is used by [`TextReader::from_windows_1252`].
+pub struct Windows1252Reader<Inner: Read> {
+    inner: Inner,
+
+    /// Decoded output which hasn't been returned yet, starting at `pos`.
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<Inner: Read> Windows1252Reader<Inner> {
+    /// Construct a new instance of `Windows1252Reader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Return the underlying stream object, discarding any input which has
+    /// been decoded but not yet returned.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: Read> Read for Windows1252Reader<Inner> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.pos == self.buffer.len() {
+            let mut raw_bytes = [0_u8; 4096];
+            let n = self.inner.read(&mut raw_bytes)?;
+            self.buffer.clear();
+            self.pos = 0;
+            let mut utf8 = [0_u8; 4];
+            for b in &raw_bytes[..n] {
+                let c = decode_windows_1252(*b);
+                self.buffer
+                    .extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+            }
+        }
+
+        let n = min(buf.len(), self.buffer.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl<Inner: Read + Debug> Debug for Windows1252Reader<Inner> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("Windows1252Reader");
+        b.field("inner", &self.inner);
+        b.finish()
+    }
+}
+
+/// Decode a byte in the Windows-1252 encoding.
+fn decode_windows_1252(b: u8) -> char {
+    // WHATWG Encoding Standard, index-windows-1252.txt
+    #[rustfmt::skip]
+    const HIGH: [char; 32] = [
+        '\u{20ac}', '\u{81}',   '\u{201a}', '\u{192}',  '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+        '\u{2c6}',  '\u{2030}', '\u{160}',  '\u{2039}', '\u{152}',  '\u{8d}',   '\u{17d}',  '\u{8f}',
+        '\u{90}',   '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+        '\u{2dc}',  '\u{2122}', '\u{161}',  '\u{203a}', '\u{153}',  '\u{9d}',   '\u{17e}',  '\u{178}',
+    ];
+    match b {
+        0x80..=0x9f => HIGH[usize::from(b - 0x80)],
+        _ => char::from(b),
+    }
+}
+
 #[cfg(not(windows))]
 impl<Inner: ReadStrLayered + AsRawFd> AsRawFd for TextReader<Inner> {
     #[inline]
@@ -312,6 +835,148 @@ fn test_bom() {
     );
 }
 
+#[test]
+fn test_had_bom() {
+    fn had_bom<Inner: ReadStrLayered>(mut reader: TextReader<Inner>) -> bool {
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        reader.had_bom()
+    }
+
+    assert!(had_bom(TextReader::new("\u{feff}hello\n".as_bytes())));
+    assert!(had_bom(TextReader::new("\u{feff}".as_bytes())));
+    assert!(!had_bom(TextReader::new("hello\n".as_bytes())));
+    assert!(!had_bom(TextReader::new("hello\u{feff}\n".as_bytes())));
+    assert!(!had_bom(TextReader::new("".as_bytes())));
+    assert!(!had_bom(TextReader::with_bom_as_content(
+        "\u{feff}hello\n".as_bytes()
+    )));
+}
+
+#[test]
+fn test_recommended_buffer() {
+    type Reader<'a> = TextReader<Utf8Reader<LayeredReader<&'a [u8]>>>;
+
+    let mut buf = Reader::recommended_buffer();
+    assert_eq!(buf.len(), Reader::MIN_BUFFER_SIZE);
+
+    let mut reader = Reader::new(b"hello\r\nworld".as_slice());
+    let mut v = Vec::new();
+    loop {
+        let (size, status) = reader.read_with_status(&mut buf).unwrap();
+        v.extend_from_slice(&buf[..size]);
+        if status.is_end() {
+            break;
+        }
+    }
+    assert_eq!(String::from_utf8(v).unwrap(), "hello\nworld\n");
+
+    let mut small = vec![0; Reader::MIN_BUFFER_SIZE - 1];
+    let mut reader = Reader::new(b"hello".as_slice());
+    assert_eq!(
+        reader.read_with_status(&mut small).unwrap_err().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_line_break_policy() {
+    fn translate(policy: LineBreakPolicy) -> String {
+        let mut reader =
+            TextReader::with_line_break_policy("a\u{85}b\u{2028}c\u{2029}d".as_bytes(), policy);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        s
+    }
+
+    type P = LineBreakPolicy;
+    assert_eq!(translate(P::empty()), "a b c d\n");
+    assert_eq!(translate(P::NEL), "a\nb c d\n");
+    assert_eq!(translate(P::LS), "a b\nc d\n");
+    assert_eq!(translate(P::PS), "a b c\nd\n");
+    assert_eq!(translate(P::NEL | P::LS), "a\nb\nc d\n");
+    assert_eq!(translate(P::NEL | P::PS), "a\nb c\nd\n");
+    assert_eq!(translate(P::LS | P::PS), "a b\nc\nd\n");
+    assert_eq!(translate(P::all()), "a\nb\nc\nd\n");
+
+    // The older constructors are equivalent to the corresponding policies.
+    let mut s = String::new();
+    TextReader::with_nel_compatibility("a\u{85}b\u{2028}c\u{2029}d".as_bytes())
+        .unwrap()
+        .read_to_string(&mut s)
+        .unwrap();
+    assert_eq!(s, translate(P::NEL));
+    let mut s = String::new();
+    TextReader::with_lsps_compatibility("a\u{85}b\u{2028}c\u{2029}d".as_bytes())
+        .unwrap()
+        .read_to_string(&mut s)
+        .unwrap();
+    assert_eq!(s, translate(P::LS | P::PS));
+}
+
+#[test]
+fn test_from_iter() {
+    fn translate(bytes: &[u8]) -> String {
+        let mut reader = TextReader::from_iter(bytes.iter().copied());
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        s
+    }
+
+    assert_eq!(translate(b""), "");
+    assert_eq!(translate(b"hello\r\nworld"), "hello\nworld\n");
+    assert_eq!(translate(b"\xffA\xcc\x8a\n"), "\u{fffd}\u{c5}\n");
+
+    let long = "x\u{301}\n".repeat(4096);
+    assert_eq!(translate(long.as_bytes()), long);
+}
+
+#[test]
+fn test_from_windows_1252() {
+    fn translate(bytes: &[u8]) -> String {
+        let mut reader = TextReader::from_windows_1252(bytes);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        s
+    }
+
+    assert_eq!(translate(b""), "");
+    assert_eq!(translate(b"hello\r\nworld"), "hello\nworld\n");
+    assert_eq!(
+        translate(b"\x80 \x85 \x99 \x9f"),
+        "\u{20ac} \u{2026} \u{2122} \u{178}\n"
+    );
+    assert_eq!(translate(b"\xa0\xc5\xe9\xff"), "\u{a0}\u{c5}\u{e9}\u{ff}\n");
+
+    // Undefined bytes decode to C1 control codes, which are replaced.
+    assert_eq!(translate(b"a\x81b\x9dc"), "a\u{fffd}b\u{fffd}c\n");
+
+    // Bytes which decode to multi-byte UTF-8 sequences span reads.
+    let long = b"\xe9".repeat(4096 * 3);
+    assert_eq!(translate(&long), format!("{}\n", "\u{e9}".repeat(4096 * 3)));
+}
+
+#[test]
+fn test_bom_as_content() {
+    fn translate(bytes: &[u8]) -> String {
+        let mut reader = TextReader::with_bom_as_content(bytes);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        s
+    }
+
+    assert_eq!(translate("\u{feff}".as_bytes()), "\u{2060}\n");
+    assert_eq!(
+        translate("\u{feff}hello\u{feff}world\u{feff}".as_bytes()),
+        "\u{2060}hello\u{2060}world\u{2060}\n"
+    );
+    assert_eq!(translate("hello\n".as_bytes()), "hello\n");
+
+    // Contrast with the default behavior, which removes a leading BOM.
+    test("\u{feff}hello\n".as_bytes(), "hello\n");
+    assert_eq!(translate("\u{feff}hello\n".as_bytes()), "\u{2060}hello\n");
+}
+
 #[test]
 fn test_crlf() {
     test(b"\r\n", "\n");
@@ -330,6 +995,56 @@ fn test_ff() {
     test(b"\x0chello\x0cworld\x0c", " hello world \n");
 }
 
+#[cfg(test)]
+fn test_form_feed_compatibility(bytes: &[u8], s: &str) {
+    let mut reader = TextReader::with_form_feed_compatibility(bytes).unwrap();
+    let mut t = String::new();
+    reader.read_to_string(&mut t).unwrap();
+    assert_eq!(t, s);
+
+    let mut reader = TextReader::from_utf8_with_form_feed_compatibility(Utf8Reader::new(
+        layered_io::SliceReader::new(bytes),
+    ))
+    .unwrap();
+    let mut v = Vec::new();
+    let mut buf = [0; basic_text_internals::unicode::NORMALIZATION_BUFFER_SIZE];
+    loop {
+        let (size, status) = reader.read_with_status(&mut buf).unwrap();
+        v.extend_from_slice(&buf[..size]);
+        if status.is_end() {
+            break;
+        }
+    }
+    assert_eq!(String::from_utf8(v).unwrap(), s);
+}
+
+#[test]
+fn test_ff_compatibility() {
+    test_form_feed_compatibility(b"\x0c", "\x0c\n");
+    test_form_feed_compatibility(b"\x0c\n", "\x0c\n");
+    test_form_feed_compatibility(b"\x0c\r\n", "\x0c\n");
+    test_form_feed_compatibility(b"\x0c\x0c", "\x0c\n\x0c\n");
+    test_form_feed_compatibility(
+        b"\x0chello\x0cworld\x0c",
+        "\x0c\nhello\n\x0c\nworld\n\x0c\n",
+    );
+    test_form_feed_compatibility(b"hello\n\x0c\nworld\n", "hello\n\x0c\nworld\n");
+}
+
+#[test]
+fn test_ff_compatibility_not_text() {
+    let mut reader = TextReader::with_form_feed_compatibility(&b"a\x0c\nb\n"[..]).unwrap();
+    let mut t = TextString::new();
+    reader.read_to_text_string(&mut t).unwrap_err();
+    assert!(t.is_empty());
+
+    let mut reader = TextReader::with_form_feed_compatibility(&b"a\nb\n"[..]).unwrap();
+    let mut t = TextString::new();
+    reader.read_line_text(&mut t).unwrap_err();
+    let mut buf = TextSubstring::new();
+    reader.read_exact_graphemes(1, &mut buf).unwrap_err();
+}
+
 #[test]
 fn test_del() {
     test(b"\x7f", "\u{fffd}\n");
@@ -416,6 +1131,117 @@ fn test_osc() {
     );
 }
 
+#[test]
+fn test_read_exact_graphemes() {
+    let input = "ab\u{1f415}\u{200d}\u{1f9ba}x\u{301}\r\ncd";
+    let mut reader = TextReader::new(input.as_bytes());
+    let mut s = TextSubstring::new();
+    reader.read_exact_graphemes(0, &mut s).unwrap();
+    assert_eq!(s.as_str(), "");
+    reader.read_exact_graphemes(1, &mut s).unwrap();
+    assert_eq!(s.as_str(), "a");
+    reader.read_exact_graphemes(2, &mut s).unwrap();
+    assert_eq!(s.as_str(), "ab\u{1f415}\u{200d}\u{1f9ba}");
+    reader.read_exact_graphemes(2, &mut s).unwrap();
+    assert_eq!(s.as_str(), "ab\u{1f415}\u{200d}\u{1f9ba}x\u{301}\n");
+
+    // The rest of the input is still available to other reads.
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "cd\n");
+
+    let mut reader = TextReader::new("abc".as_bytes());
+    let mut s = TextSubstring::new();
+    reader.read_exact_graphemes(4, &mut s).unwrap();
+    assert_eq!(s.as_str(), "abc\n");
+
+    let mut reader = TextReader::new("abc".as_bytes());
+    let mut s = TextSubstring::new();
+    let err = reader.read_exact_graphemes(5, &mut s).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(s.as_str(), "");
+
+    // Long input spanning several reads.
+    let long = "x\u{301}".repeat(5000);
+    let mut reader = TextReader::new(long.as_bytes());
+    let mut s = TextSubstring::new();
+    reader.read_exact_graphemes(4999, &mut s).unwrap();
+    assert_eq!(s.as_str(), "x\u{301}".repeat(4999));
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "x\u{301}\n");
+}
+
+#[test]
+fn test_source_offsets() {
+    fn offsets(input: &str) -> (String, Vec<usize>) {
+        let mut reader = TextReader::new(input.as_bytes());
+        reader.capture_source_offsets();
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        let offsets = reader.take_source_offsets();
+        assert_eq!(offsets.len(), s.chars().count());
+        (s, offsets)
+    }
+
+    assert_eq!(offsets(""), (String::new(), vec![]));
+    assert_eq!(offsets("ab\n"), ("ab\n".to_owned(), vec![0, 1, 2]));
+    assert_eq!(offsets("a\rb"), ("a\nb\n".to_owned(), vec![0, 1, 2, 3]));
+    assert_eq!(
+        offsets("a\r\rb"),
+        ("a\n\nb\n".to_owned(), vec![0, 1, 2, 3, 4])
+    );
+    assert_eq!(
+        offsets("\x1b[31mred\x1b[0m\n"),
+        ("red\n".to_owned(), vec![5, 6, 7, 12])
+    );
+    assert_eq!(
+        offsets("a\x0c\x0cb\n"),
+        ("a b\n".to_owned(), vec![0, 1, 3, 4])
+    );
+    assert_eq!(
+        offsets("\u{301}x"),
+        ("\u{34f}\u{301}x\n".to_owned(), vec![0, 0, 2, 3])
+    );
+    assert_eq!(
+        offsets("xA\u{30a}y\n"),
+        ("x\u{c5}y\n".to_owned(), vec![0, 1, 4, 5])
+    );
+    assert_eq!(
+        offsets("x\u{200d}"),
+        ("x\u{200d}\u{34f}\n".to_owned(), vec![0, 1, 4, 4])
+    );
+
+    // Offsets across multiple reads. The combining mark is normalized with
+    // the scalar value before it.
+    let long = "x\u{301}\r\n".repeat(2000);
+    let (s, offsets) = offsets(&long);
+    assert_eq!(s, "x\u{301}\n".repeat(2000));
+    for (i, offset) in offsets.iter().enumerate() {
+        assert_eq!(*offset, i / 3 * 5 + [0, 0, 3][i % 3]);
+    }
+}
+
+#[test]
+fn test_capture_escape_sequences() {
+    let mut reader = TextReader::new(&b"\x1b[31mred\x1b[0m \x1b]0;title\x07\x1bcx\x1b"[..]);
+    reader.capture_escape_sequences();
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "red x\n");
+    assert_eq!(
+        reader.take_escape_sequences(),
+        vec!["\x1b[31m", "\x1b[0m", "\x1b]0;title\x07", "\x1bc", "\x1b"]
+    );
+    assert!(reader.take_escape_sequences().is_empty());
+
+    let mut reader = TextReader::new(&b"\x1b[31mred"[..]);
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "red\n");
+    assert!(reader.take_escape_sequences().is_empty());
+}
+
 #[test]
 fn test_linux() {
     test(b"\x1b[[A", "\n");
@@ -438,5 +1264,160 @@ fn test_dddha() {
     test("\u{1109a}\n".as_bytes(), "\u{1109a}\n");
 }
 
+#[test]
+fn test_read_line_text() {
+    let mut reader = TextReader::new("hello\r\nA\u{30a}\n\nworld".as_bytes());
+    let mut line = TextString::new();
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 6);
+    assert_eq!(line, "hello\n");
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 3);
+    assert_eq!(line, "hello\n\u{c5}\n");
+
+    // Reads can be mixed with line reads.
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "\nworld\n");
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 0);
+
+    // Lines longer than the underlying read size are read in full.
+    let long = "x".repeat(10000);
+    let mut reader = TextReader::new(long.as_bytes());
+    let mut line = TextString::new();
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 10001);
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 0);
+    assert_eq!(line, format!("{}\n", long));
+}
+
+#[test]
+fn test_read_line_text_non_starter() {
+    let mut reader = TextReader::new("a\n\u{301}b\nc\n".as_bytes());
+    let mut line = TextString::new();
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 2);
+    assert_eq!(line, "a\n");
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 4);
+    assert_eq!(line, "a\n\u{301}b\n");
+
+    // Read each line into a new buffer.
+    let mut reader = TextReader::new("a\n\u{301}b\nc\n".as_bytes());
+    let mut line = TextString::new();
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 2);
+    assert_eq!(line, "a\n");
+    line.clear();
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 6);
+    assert_eq!(line, "\u{34f}\u{301}b\n");
+    line.clear();
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 2);
+    assert_eq!(line, "c\n");
+    assert_eq!(reader.read_line_text(&mut line).unwrap(), 0);
+}
+
+#[test]
+fn test_max_buffer() {
+    let mut reader = TextReader::with_max_buffer("hello\nworld\n".as_bytes(), 8);
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello\nworld\n");
+
+    let long = "x".repeat(10000);
+    let mut reader = TextReader::with_max_buffer(long.as_bytes(), 8192);
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap_err();
+    reader.abandon();
+
+    let mut reader = TextReader::with_max_buffer(long.as_bytes(), 8192);
+    let mut line = TextString::new();
+    reader.read_line_text(&mut line).unwrap_err();
+
+    // A line containing CGJs, which are normalization boundaries, is still
+    // bounded.
+    let cgjs = "x\u{34f}".repeat(5000);
+    let mut reader = TextReader::with_max_buffer(cgjs.as_bytes(), 8192);
+    let mut line = TextString::new();
+    reader.read_line_text(&mut line).unwrap_err();
+
+    let long_lines = format!("{}\n", "x".repeat(4000)).repeat(4);
+    let mut reader = TextReader::with_max_buffer(long_lines.as_bytes(), 8192);
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, long_lines);
+}
+
 // TODO: Test Stream-Safe
 // TODO: test for nonstarter after push
+
+#[test]
+fn test_get_ref() {
+    let mut reader = TextReader::new(b"hello\n".as_slice());
+    let _: &Utf8Reader<LayeredReader<&[u8]>> = reader.get_ref();
+    let _: &mut Utf8Reader<LayeredReader<&[u8]>> = reader.get_mut();
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello\n");
+}
+
+#[test]
+fn test_detected_line_ending() {
+    fn detect(bytes: &[u8]) -> Option<LineEnding> {
+        let mut reader = TextReader::new(bytes);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        reader.detected_line_ending()
+    }
+
+    assert_eq!(detect(b""), None);
+    assert_eq!(detect(b"hello"), None);
+    assert_eq!(detect(b"a\nb\n"), Some(LineEnding::Lf));
+    assert_eq!(detect(b"a\r\nb"), Some(LineEnding::CrLf));
+    assert_eq!(detect(b"a\rb\r"), Some(LineEnding::Cr));
+    assert_eq!(detect(b"a\r\nb\n"), Some(LineEnding::Mixed));
+    assert_eq!(detect(b"a\rb\r\n"), Some(LineEnding::Mixed));
+    assert_eq!(detect(b"a\x0c\r\nb\x1b[m\r\n"), Some(LineEnding::CrLf));
+}
+
+#[test]
+fn test_tab_policy() {
+    fn translate(bytes: &[u8], policy: TabPolicy) -> io::Result<String> {
+        let mut reader = TextReader::with_tab_policy(bytes, policy);
+        let mut s = String::new();
+        reader.read_to_string(&mut s)?;
+        Ok(s)
+    }
+
+    let input = "\tif x {\n\t\ty();\n}\n".as_bytes();
+    assert_eq!(
+        translate(input, TabPolicy::Preserve).unwrap(),
+        "\tif x {\n\t\ty();\n}\n"
+    );
+    assert_eq!(
+        translate(input, TabPolicy::ExpandTo(4)).unwrap(),
+        "    if x {\n        y();\n}\n"
+    );
+    assert_eq!(
+        translate(input, TabPolicy::Reject).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert_eq!(
+        translate(b"no tabs\n", TabPolicy::Reject).unwrap(),
+        "no tabs\n"
+    );
+
+    // Columns are counted in display width, skipping removed escape
+    // sequences, and restart after each kind of newline.
+    assert_eq!(
+        translate("a\u{301}\tb".as_bytes(), TabPolicy::ExpandTo(4)).unwrap(),
+        "\u{e1}   b\n"
+    );
+    assert_eq!(
+        translate("\u{4e00}\tb".as_bytes(), TabPolicy::ExpandTo(4)).unwrap(),
+        "\u{4e00}  b\n"
+    );
+    assert_eq!(
+        translate(b"abc\x1b[31m\td\r\te", TabPolicy::ExpandTo(8)).unwrap(),
+        "abc     d\n        e\n"
+    );
+
+    // The default is to preserve tabs.
+    let mut s = String::new();
+    TextReader::new(input).read_to_string(&mut s).unwrap();
+    assert_eq!(s, translate(input, TabPolicy::Preserve).unwrap());
+}
@@ -0,0 +1,110 @@
+//! Running regular expressions from the `regex` crate on text strings.
+//!
+//! The regular expressions operate on scalar values, not grapheme clusters,
+//! so for example `.` can match a base scalar value without its combining
+//! marks. Matches are still returned as [`TextSubstr`]s, since any
+//! substring of a Basic Text string which starts and ends on scalar value
+//! boundaries is a valid text substring.
+
+use crate::{TextStr, TextSubstr};
+use regex::{Captures, Regex};
+
+impl TextStr {
+    /// Returns the leftmost-first match of `re` in this text string slice,
+    /// if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextStr;
+    /// use regex::Regex;
+    ///
+    /// let re = Regex::new(r"[0-9]+").unwrap();
+    /// let s = TextStr::from_text("route 66").unwrap();
+    /// assert_eq!(s.regex_find(&re).unwrap(), "66");
+    /// ```
+    #[inline]
+    pub fn regex_find<'a>(&'a self, re: &Regex) -> Option<&'a TextSubstr> {
+        re.find(&self.0)
+            .map(|m| unsafe { TextSubstr::from_text_unchecked(m.as_str()) })
+    }
+
+    /// Returns the capture groups of the leftmost-first match of `re` in
+    /// this text string slice, if any.
+    #[inline]
+    pub fn regex_captures<'a>(&'a self, re: &Regex) -> Option<TextCaptures<'a>> {
+        re.captures(&self.0).map(TextCaptures)
+    }
+
+    /// Returns an iterator over the text substring slices of this text
+    /// string slice separated by matches of `re`.
+    #[inline]
+    pub fn regex_split<'a: 'r, 'r>(
+        &'a self,
+        re: &'r Regex,
+    ) -> impl Iterator<Item = &'a TextSubstr> + 'r {
+        re.split(&self.0)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+}
+
+/// The capture groups of a regular expression match in a [`TextStr`].
+///
+/// This is returned by [`TextStr::regex_captures`].
+#[derive(Debug)]
+pub struct TextCaptures<'a>(Captures<'a>);
+
+impl<'a> TextCaptures<'a> {
+    /// Returns the text substring slice matched by the capture group at
+    /// index `i`, or `None` if the group didn't participate in the match.
+    /// Index 0 is the whole match.
+    #[inline]
+    pub fn get(&self, i: usize) -> Option<&'a TextSubstr> {
+        self.0
+            .get(i)
+            .map(|m| unsafe { TextSubstr::from_text_unchecked(m.as_str()) })
+    }
+
+    /// Returns the text substring slice matched by the capture group named
+    /// `name`, or `None` if there's no such group or it didn't participate
+    /// in the match.
+    #[inline]
+    pub fn name(&self, name: &str) -> Option<&'a TextSubstr> {
+        self.0
+            .name(name)
+            .map(|m| unsafe { TextSubstr::from_text_unchecked(m.as_str()) })
+    }
+
+    /// Returns the number of capture groups, including the implicit group
+    /// for the whole match.
+    #[allow(clippy::len_without_is_empty)]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[test]
+fn regex() {
+    let re = Regex::new(r"(?<key>\w+)=(?<value>\w*)").unwrap();
+    let s = TextStr::from_text("a=1, caf\u{e9}=cr\u{e8}me").unwrap();
+
+    assert_eq!(s.regex_find(&re).unwrap(), "a=1");
+
+    let caps = s.regex_captures(&re).unwrap();
+    assert_eq!(caps.len(), 3);
+    assert_eq!(caps.get(0).unwrap(), "a=1");
+    assert_eq!(caps.name("key").unwrap(), "a");
+    assert_eq!(caps.name("value").unwrap(), "1");
+    assert!(caps.name("other").is_none());
+
+    let parts = s
+        .regex_split(&Regex::new(r",\s*").unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(parts, ["a=1", "caf\u{e9}=cr\u{e8}me"]);
+
+    // Regexes match scalar values, so `.` can match a base without its
+    // combining mark.
+    let s = TextStr::from_text("x\u{323}\u{302}").unwrap();
+    assert_eq!(s.regex_find(&Regex::new(".").unwrap()).unwrap(), "x");
+}
@@ -1,29 +1,38 @@
 //! The `TextString` and `TextStr` types.
 
-use crate::{ReadText, TextReader, TextSubstr, TextWriter};
-use basic_text_internals::unicode::{BOM, CGJ, WJ};
-use basic_text_internals::{is_basic_text, is_basic_text_start};
+use crate::{ReadText, TextReader, TextSubstr, TextSubstring, TextWriter};
+use basic_text_internals::unicode::{BOM, CGJ, ESC, NORMALIZATION_BUFFER_SIZE, WJ, ZWJ};
+use basic_text_internals::unicode_normalization::{
+    is_nfc, is_nfc_stream_safe_quick, IsNormalized, UnicodeNormalization,
+};
+use basic_text_internals::{
+    check_basic_text_char, is_basic_text, is_basic_text_end, is_basic_text_start,
+    is_basic_text_substr, is_default_ignorable, is_extended_pictographic, BasicTextError,
+    StreamSafeNfc,
+};
+use caseless::Caseless;
 use layered_io::Bufferable;
 use std::borrow::{Borrow, BorrowMut, Cow};
-use std::cmp::Ordering;
-#[cfg(try_reserve)]
+use std::cmp::{min, Ordering};
 use std::collections::TryReserveError;
 use std::error::Error;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fmt::{self, Debug, Display, Formatter};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Read, Write};
 use std::net::{SocketAddr, ToSocketAddrs};
-use std::ops::{Add, AddAssign, Deref, DerefMut, Index, Range, RangeFrom, RangeTo};
-use std::path::Path;
+use std::ops::{Add, AddAssign, Deref, DerefMut, Index, Range, RangeBounds, RangeFrom, RangeTo};
+use std::path::{Path, PathBuf};
 #[cfg(pattern)]
-use std::str::pattern::{Pattern, ReverseSearcher};
+use std::str::pattern::{DoubleEndedSearcher, Pattern, ReverseSearcher};
 use std::str::{
     self, Bytes, CharIndices, Chars, EncodeUtf16, EscapeDebug, EscapeDefault, EscapeUnicode,
     FromStr, Lines, MatchIndices, Matches, RMatchIndices, RMatches, Utf8Error,
 };
 use std::string::FromUtf8Error;
 use std::vec;
+use unicode_segmentation::{GraphemeCursor, Graphemes, UnicodeSegmentation};
+use unicode_width::UnicodeWidthStr;
 use utf8_io::WriteStr;
 
 /// A Basic Text encoded, growable string.
@@ -181,6 +190,20 @@ impl TextString {
         Cow::Owned(Self::from_text_lossy(&String::from_utf8_lossy(v)).into_owned())
     }
 
+    /// Converts a vector of bytes to Basic Text, including invalid
+    /// characters.
+    ///
+    /// If `v` is already valid Basic Text, its allocation is reused.
+    #[inline]
+    #[must_use]
+    pub fn from_text_vec_lossy(v: Vec<u8>) -> Self {
+        match String::from_utf8(v) {
+            Ok(s) if is_basic_text(&s) => Self(s),
+            Ok(s) => Self::from_text_lossy(&s).into_owned(),
+            Err(err) => Self::from_text_bytes_lossy(err.as_bytes()).into_owned(),
+        }
+    }
+
     /// Converts a string to Basic Text, including invalid characters.
     #[inline]
     #[must_use]
@@ -208,7 +231,66 @@ impl TextString {
         Cow::Owned(unsafe { Self::from_text_unchecked(text) })
     }
 
-    // TODO: from_raw_parts, from_utf16*
+    /// Converts a `char` to Basic Text, replacing it if it's invalid.
+    ///
+    /// This applies the same replacements as [`from_text_lossy`], so the
+    /// result may be empty, as for an escape, or contain more than one
+    /// `char`, as for a non-starter, which is guarded with a U+34F (CGJ).
+    ///
+    /// [`from_text_lossy`]: Self::from_text_lossy
+    #[inline]
+    #[must_use]
+    pub fn from_char_lossy(c: char) -> Self {
+        Self::from_text_lossy(c.encode_utf8(&mut [0; 4])).into_owned()
+    }
+
+    /// Converts UTF-16 code units to Basic Text, including invalid
+    /// characters.
+    ///
+    /// Each unpaired surrogate is replaced by one U+FFFD, as with
+    /// [`String::from_utf16_lossy`], and the result is then repaired in the
+    /// same way as [`from_text_lossy`].
+    ///
+    /// [`from_text_lossy`]: Self::from_text_lossy
+    #[inline]
+    #[must_use]
+    pub fn from_utf16_lossy(v: &[u16]) -> Self {
+        Self::from_text_lossy(&String::from_utf16_lossy(v)).into_owned()
+    }
+
+    /// Concatenates Basic Text substrings into a `TextString`.
+    ///
+    /// Concatenated substrings may not be in Stream-Safe NFC at the seams, so
+    /// the result is renormalized. If the result would begin with a
+    /// non-starter or end with a ZWJ or Prepend, a CGJ is inserted to guard
+    /// it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::{text_substr, TextString};
+    ///
+    /// // Assemble the "Service Dog" ZWJ Sequence "🐕‍🦺" from parts.
+    /// let s = TextString::from_substrs([text_substr!("🐕\u{200d}"), text_substr!("🦺")]).unwrap();
+    /// assert_eq!(s, "🐕\u{200d}🦺");
+    /// ```
+    pub fn from_substrs<'a>(
+        iter: impl IntoIterator<Item = &'a TextSubstr>,
+    ) -> Result<Self, TextError> {
+        let mut s = String::new();
+        for substr in iter {
+            s.push_str(substr.as_str());
+        }
+
+        renormalize(&mut s);
+        guard_start(&mut s);
+        guard_end(&mut s);
+
+        TextStr::from_text(&s)?;
+        Ok(unsafe { Self::from_text_unchecked(s) })
+    }
+
+    // TODO: from_raw_parts, from_utf16
 
     /// Converts a vector of bytes to a `TextString` without checking that the
     /// string contains valid Basic Text.
@@ -218,10 +300,16 @@ impl TextString {
     /// This function is unsafe because it does not check that the bytes passed
     /// to it are valid Basic Text. If this constraint is violated, undefined
     /// behavior results, as the rest of this crate assumes that `&TextStr`s
-    /// are valid Basic Text.
+    /// are valid Basic Text. In debug builds, this is checked with a
+    /// `debug_assert!`.
     #[inline]
     #[must_use]
     pub unsafe fn from_text_vec_unchecked(vec: Vec<u8>) -> Self {
+        debug_assert!(
+            str::from_utf8(&vec).is_ok_and(is_basic_text),
+            "invalid Basic Text: {:?}",
+            vec
+        );
         Self::from_text_unchecked(String::from_utf8_unchecked(vec))
     }
 
@@ -254,6 +342,29 @@ impl TextString {
         self.0.into_bytes()
     }
 
+    /// Splits a `TextString` into owned lines, split at each '\n'.
+    ///
+    /// The '\n's are not included in the lines. As with [`str::lines`], a
+    /// trailing '\n' doesn't produce a trailing empty line, so `"a\nb\n"`
+    /// and `"a\nb"` both produce `["a", "b"]`, and an empty string produces
+    /// no lines.
+    ///
+    /// Each line is valid Basic Text on its own; a line which begins with a
+    /// non-starter has a CGJ prepended, and a line which ends with a ZWJ or
+    /// Prepend has a CGJ appended.
+    #[must_use]
+    pub fn into_lines(self) -> Vec<Self> {
+        self.0
+            .lines()
+            .map(|line| {
+                let mut line = line.to_owned();
+                guard_start(&mut line);
+                guard_end(&mut line);
+                unsafe { Self::from_text_unchecked(line) }
+            })
+            .collect()
+    }
+
     /// Extracts a UTF-8 string slice containing the entire `TextString`.
     #[inline]
     #[must_use]
@@ -291,6 +402,56 @@ impl TextString {
         self.0.push_str(&s.0);
     }
 
+    /// Appends a given text string onto the end of this `TextString`, like
+    /// [`push_text`], but if `s` is owned and this `TextString` is empty,
+    /// takes ownership of `s`'s buffer instead of copying it, unless this
+    /// `TextString` has more capacity reserved.
+    ///
+    /// [`push_text`]: Self::push_text
+    #[inline]
+    pub fn push_cow(&mut self, s: Cow<'_, TextStr>) {
+        match s {
+            Cow::Owned(s) if self.0.is_empty() && s.0.capacity() >= self.0.capacity() => *self = s,
+            s => self.push_text(&s),
+        }
+    }
+
+    /// Appends a string slice onto the end of this `TextString`, if it's
+    /// valid Basic Text, leaving this `TextString` unchanged otherwise.
+    ///
+    /// Appending can also fail if `s` is valid on its own but the result
+    /// wouldn't be in NFC, such as when a Hangul syllable is split between
+    /// the end of this `TextString` and the start of `s`. In that case, the
+    /// error's `valid_up_to` is 0.
+    #[inline]
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TextError> {
+        let s = TextStr::from_text(s)?;
+        if !is_nfc_seam(&self.0, &s.0) {
+            return Err(TextError { valid_up_to: 0 });
+        }
+        self.push_text(s);
+        Ok(())
+    }
+
+    /// Appends a string slice onto the end of this `TextString`, replacing
+    /// invalid characters in the same way as [`from_text_lossy`],
+    /// and renormalizing where it meets the existing contents as needed.
+    ///
+    /// [`from_text_lossy`]: Self::from_text_lossy
+    #[inline]
+    pub fn push_str_lossy(&mut self, s: &str) {
+        let s = TextString::from_text_lossy(s);
+        if is_nfc_seam(&self.0, &s.0) {
+            self.push_text(&s);
+        } else {
+            let start = self.0.char_indices().next_back().map_or(0, |(i, _)| i);
+            self.0.push_str(&s.0);
+            let tail = StreamSafeNfc::new(self.0[start..].chars()).collect::<String>();
+            self.0.truncate(start);
+            self.0.push_str(&tail);
+        }
+    }
+
     /// Returns this `TextString`'s capacity, in bytes.
     #[inline]
     #[must_use]
@@ -314,7 +475,6 @@ impl TextString {
 
     /// Tries to reserve capacity for at least `additional` more elements to
     /// be inserted in the given `TextString`.
-    #[cfg(try_reserve)]
     #[inline]
     pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.0.try_reserve(additional)
@@ -322,7 +482,6 @@ impl TextString {
 
     /// Tries to reserves the minimum capacity for exactly `additional` more
     /// elements to be inserted in the given `TextString`.
-    #[cfg(try_reserve)]
     #[inline]
     pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.0.try_reserve_exact(additional)
@@ -334,8 +493,7 @@ impl TextString {
         self.0.shrink_to_fit();
     }
 
-    /// Shrinks the capacity of this `String` with a lower bound.
-    #[cfg(shrink_to)]
+    /// Shrinks the capacity of this `TextString` with a lower bound.
     #[inline]
     pub fn shrink_to(&mut self, min_capacity: usize) {
         self.0.shrink_to(min_capacity);
@@ -349,7 +507,21 @@ impl TextString {
         self.0.as_bytes()
     }
 
-    // TODO: truncate, pop, remove, retain, insert? ditto
+    // TODO: truncate, pop, remove, insert? ditto
+
+    /// Retains only the `char`s specified by the predicate.
+    ///
+    /// Removing a `char` may strand a combining mark or allow its neighbors
+    /// to compose, so the result is renormalized. If the result would begin
+    /// with a non-starter or end with a ZWJ or Prepend, a U+34F (CGJ) is
+    /// inserted to guard it.
+    #[inline]
+    pub fn retain(&mut self, f: impl FnMut(char) -> bool) {
+        self.0.retain(f);
+        renormalize(&mut self.0);
+        guard_start(&mut self.0);
+        guard_end(&mut self.0);
+    }
     // TODO: insert_str? We could do CGJ's where needed there?
 
     /// Returns a mutable reference to the contents of this `TextString`.
@@ -392,15 +564,109 @@ impl TextString {
         self.0.is_empty()
     }
 
-    // TODO: split_off?
+    /// Splits the string into two at the given byte index, returning the
+    /// tail.
+    ///
+    /// The tail may begin with a non-starter, so it's returned as a
+    /// `TextSubstring`. If splitting leaves `self` ending with a ZWJ or
+    /// Prepend, such as when splitting within a ZWJ sequence, a U+34F (CGJ)
+    /// is appended to `self` to guard it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not on a `char` boundary, or if it is beyond the
+    /// end of the string.
+    #[inline]
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> TextSubstring {
+        let tail = self.0.split_off(at);
+        guard_end(&mut self.0);
+
+        // Safety: The tail of a Basic Text string is a valid Basic Text
+        // substring.
+        unsafe { TextSubstring::from_text_unchecked(tail) }
+    }
 
-    /// Truncates this `String`, removing all contents.
+    /// Truncates this `TextString`, removing all contents.
+    ///
+    /// This keeps the capacity of the buffer, so it can be reused, such as
+    /// with [`set_from_text`].
+    ///
+    /// [`set_from_text`]: Self::set_from_text
     #[inline]
     pub fn clear(&mut self) {
         self.0.clear();
     }
 
-    // TODO: drain, replace_range?
+    /// Replaces the contents of this `TextString` with `s`, if it's valid
+    /// Basic Text, reusing this `TextString`'s buffer. If `s` isn't valid,
+    /// this `TextString` is left unchanged.
+    ///
+    /// This avoids allocating a new buffer for each string when validating
+    /// many strings in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextString;
+    ///
+    /// let mut buf = TextString::with_capacity(64);
+    /// for s in ["one", "two\x07", "three"] {
+    ///     if buf.set_from_text(s).is_ok() {
+    ///         assert!(!buf.is_empty());
+    ///     }
+    /// }
+    /// assert_eq!(buf, "three");
+    /// ```
+    #[inline]
+    pub fn set_from_text(&mut self, s: &str) -> Result<(), TextError> {
+        let s = TextStr::from_text(s)?;
+        self.0.clear();
+        self.0.push_str(&s.0);
+        Ok(())
+    }
+
+    /// Removes the specified range from the string, returning all removed
+    /// `char`s as an iterator.
+    ///
+    /// Unlike `String::drain`, the range is removed eagerly, even if the
+    /// iterator is not consumed. Joining the remaining pieces may allow
+    /// `char`s on either side of the seam to compose, so the result is
+    /// renormalized. If the result would begin with a non-starter or end with
+    /// a ZWJ or Prepend, a U+34F (CGJ) is inserted to guard it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a `char`
+    /// boundary, or if they're out of bounds.
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> impl Iterator<Item = char> {
+        let drained = self.0.drain(range).collect::<Vec<char>>();
+        renormalize(&mut self.0);
+        guard_start(&mut self.0);
+        guard_end(&mut self.0);
+        drained.into_iter()
+    }
+
+    /// Removes the specified range in the string, and replaces it with the
+    /// given string.
+    ///
+    /// Joining the pieces may allow `char`s on either side of a seam to
+    /// compose, so the result is renormalized. If the result would begin
+    /// with a non-starter or end with a ZWJ or Prepend, a U+34F (CGJ) is
+    /// inserted to guard it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a `char`
+    /// boundary, or if they're out of bounds.
+    #[inline]
+    pub fn replace_range<R: RangeBounds<usize>>(&mut self, range: R, replace_with: &TextStr) {
+        self.0.replace_range(range, replace_with.as_str());
+        renormalize(&mut self.0);
+        guard_start(&mut self.0);
+        guard_end(&mut self.0);
+    }
 
     /// Converts this `TextString` into a `Box<str>`.
     #[inline]
@@ -416,6 +682,137 @@ impl TextString {
     }
 }
 
+/// If `s` isn't in Stream-Safe NFC, normalize it.
+fn renormalize(s: &mut String) {
+    if is_nfc_stream_safe_quick(s.chars()) != IsNormalized::Yes {
+        *s = StreamSafeNfc::new(s.chars()).collect();
+    }
+}
+
+/// Test whether concatenating `before` and `after`, which are each in NFC
+/// and begin with starters, is in NFC, which isn't the case if the last
+/// scalar value of `before` composes with the first of `after`, such as
+/// Hangul conjoining jamo.
+fn is_nfc_seam(before: &str, after: &str) -> bool {
+    match (before.chars().next_back(), after.chars().next()) {
+        (Some(last), Some(first)) => is_nfc(&[last, first].iter().collect::<String>()),
+        _ => true,
+    }
+}
+
+/// Test whether `chars[i]` is a ZWJ joining two emoji in an emoji ZWJ
+/// sequence.
+fn is_emoji_zwj(chars: &[char], i: usize) -> bool {
+    let before = chars[..i]
+        .iter()
+        .rev()
+        .find(|c| !matches!(c, '\u{fe0f}' | '\u{1f3fb}'..='\u{1f3ff}'));
+    chars[i] == ZWJ
+        && before.is_some_and(|c| is_extended_pictographic(*c))
+        && chars
+            .get(i + 1)
+            .is_some_and(|c| is_extended_pictographic(*c))
+}
+
+/// If `s` begins with a non-starter, prepend a CGJ to guard it.
+fn guard_start(s: &mut String) {
+    if let Some(c) = s.chars().next() {
+        if !is_basic_text_start(c) {
+            s.insert(0, CGJ);
+        }
+    }
+}
+
+/// If `s` ends with a ZWJ or Prepend, append a CGJ to guard it.
+fn guard_end(s: &mut String) {
+    if let Some(c) = s.chars().next_back() {
+        if !is_basic_text_end(c) {
+            s.push(CGJ);
+        }
+    }
+}
+
+/// An iterator which validates a slice of bytes as Basic Text one window at a
+/// time, yielding the number of bytes validated so far.
+///
+/// This is returned by [`TextStr::validate_chunked`].
+pub struct ValidateChunked<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    done: bool,
+    error: Option<TextError>,
+}
+
+impl<'a> ValidateChunked<'a> {
+    /// Validate any remaining windows, and return the input as a `&TextStr`.
+    pub fn finish(mut self) -> Result<&'a TextStr, TextError> {
+        for result in self.by_ref() {
+            result?;
+        }
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(unsafe { TextStr::from_text_bytes_unchecked(self.bytes) }),
+        }
+    }
+
+    #[cold]
+    fn fail(&mut self, valid_up_to: usize) -> Option<Result<usize, TextError>> {
+        let error = TextError { valid_up_to };
+        self.error = Some(error);
+        self.done = true;
+        Some(Err(error))
+    }
+}
+
+impl<'a> Iterator for ValidateChunked<'a> {
+    type Item = Result<usize, TextError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // End each window before an ASCII byte. Nothing composes with, or is
+        // reordered around, an ASCII scalar value, so normalization can be
+        // checked independently on each side. ASCII bytes are also always at
+        // a UTF-8 scalar value boundary.
+        let start = self.pos;
+        let len = self.bytes.len();
+        let target = min(start + NORMALIZATION_BUFFER_SIZE, len);
+        let end = self.bytes[target..]
+            .iter()
+            .position(u8::is_ascii)
+            .map_or(len, |i| target + i);
+
+        let s = match str::from_utf8(&self.bytes[start..end]) {
+            Ok(s) => s,
+            Err(error) => return self.fail(start + error.valid_up_to()),
+        };
+
+        if start == 0 {
+            if let Some(c) = s.chars().next() {
+                if !is_basic_text_start(c) {
+                    return self.fail(0);
+                }
+            }
+        }
+        if !is_basic_text_substr(s) {
+            return self.fail(start + compute_valid_up_to(s));
+        }
+        if end == self.bytes.len() {
+            if let Some(c) = s.chars().next_back() {
+                if !is_basic_text_end(c) {
+                    return self.fail(end - c.len_utf8());
+                }
+            }
+        }
+
+        self.pos = end;
+        self.done = end == len;
+        Some(Ok(end))
+    }
+}
+
 #[cold]
 fn compute_valid_up_to(s: &str) -> usize {
     // Binary search in `s` for the place where the error starts. We do
@@ -529,6 +926,15 @@ impl BorrowMut<TextStr> for TextString {
     }
 }
 
+// `TextString`'s `Eq`, `PartialOrd`, and `Hash` impls defer to the inner
+// `String`, so they're consistent with those of `str`.
+impl Borrow<str> for TextString {
+    #[inline]
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
 impl AsMut<TextStr> for TextString {
     #[inline]
     fn as_mut(&mut self) -> &mut TextStr {
@@ -555,13 +961,14 @@ impl AddAssign<&TextStr> for TextString {
 
 impl Extend<TextString> for TextString {
     fn extend<I: IntoIterator<Item = TextString>>(&mut self, iter: I) {
-        iter.into_iter().for_each(move |s| self.push_text(&s));
+        iter.into_iter()
+            .for_each(move |s| self.push_cow(Cow::Owned(s)));
     }
 
     #[cfg(extend_one)]
     #[inline]
     fn extend_one(&mut self, s: TextString) {
-        self.push_text(&s);
+        self.push_cow(Cow::Owned(s));
     }
 }
 
@@ -579,13 +986,13 @@ impl<'a> Extend<&'a TextStr> for TextString {
 
 impl<'a> Extend<Cow<'a, TextStr>> for TextString {
     fn extend<I: IntoIterator<Item = Cow<'a, TextStr>>>(&mut self, iter: I) {
-        iter.into_iter().for_each(move |s| self.push_text(&s));
+        iter.into_iter().for_each(move |s| self.push_cow(s));
     }
 
     #[cfg(extend_one)]
     #[inline]
     fn extend_one(&mut self, s: Cow<'a, TextStr>) {
-        self.push_text(&s);
+        self.push_cow(s);
     }
 }
 
@@ -596,6 +1003,80 @@ impl TextStr {
         Self::from_text(str::from_utf8(b)?)
     }
 
+    /// Split a slice of bytes into the longest prefix which is valid Basic
+    /// Text, ending at a grapheme cluster boundary, and the remaining bytes.
+    ///
+    /// The remaining bytes begin with an incomplete UTF-8 sequence, the first
+    /// invalid region, or the part of a grapheme cluster which couldn't be
+    /// included in the prefix. This is useful for framing protocols. No
+    /// repairs are performed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextStr;
+    ///
+    /// let (text, rest) = TextStr::from_text_bytes_prefix(b"hello\x07world");
+    /// assert_eq!(text, "hello");
+    /// assert_eq!(rest, b"\x07world");
+    /// ```
+    pub fn from_text_bytes_prefix(b: &[u8]) -> (&Self, &[u8]) {
+        let s = match str::from_utf8(b) {
+            Ok(s) => s,
+            Err(error) => unsafe { str::from_utf8_unchecked(&b[..error.valid_up_to()]) },
+        };
+
+        let mut n = s.len();
+        loop {
+            match Self::from_text(&s[..n]) {
+                Ok(text) => {
+                    // The end of the bytes we have is treated as a boundary.
+                    if n == s.len()
+                        || GraphemeCursor::new(n, s.len(), true)
+                            .is_boundary(s, 0)
+                            .unwrap()
+                    {
+                        return (text, &b[n..]);
+                    }
+                    n -= 1;
+                }
+                Err(error) => n = error.valid_up_to(),
+            }
+            while !s.is_char_boundary(n) {
+                n -= 1;
+            }
+        }
+    }
+
+    /// Validate a slice of bytes as Basic Text incrementally, in windows of
+    /// about [`NORMALIZATION_BUFFER_SIZE`] bytes, so that validating a large
+    /// input, such as a memory-mapped file, can be interleaved with other
+    /// work.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextStr;
+    ///
+    /// let mut validator = TextStr::validate_chunked(b"hello\nworld\n");
+    /// while let Some(result) = validator.next() {
+    ///     let validated = result.unwrap();
+    ///     // do other work
+    /// }
+    /// assert_eq!(validator.finish().unwrap(), "hello\nworld\n");
+    /// ```
+    ///
+    /// [`NORMALIZATION_BUFFER_SIZE`]: crate::NORMALIZATION_BUFFER_SIZE
+    #[inline]
+    pub fn validate_chunked(b: &[u8]) -> ValidateChunked<'_> {
+        ValidateChunked {
+            bytes: b,
+            pos: 0,
+            done: false,
+            error: None,
+        }
+    }
+
     /// Converts a string slice to a text string slice.
     #[inline]
     pub fn from_text(s: &str) -> Result<&Self, TextError> {
@@ -624,6 +1105,18 @@ impl TextStr {
         Ok(unsafe { Self::from_text_unchecked_mut(s) })
     }
 
+    /// Checks that this text string slice contains valid Basic Text.
+    ///
+    /// This always succeeds for text string slices constructed by safe
+    /// means; it's useful for catching bugs in code which uses
+    /// [`from_text_unchecked`] and similar functions.
+    ///
+    /// [`from_text_unchecked`]: Self::from_text_unchecked
+    #[inline]
+    pub fn check_invariant(&self) -> Result<(), TextError> {
+        Self::from_text(&self.0).map(|_| ())
+    }
+
     /// Converts a slice of bytes to a text string slice without checking that
     /// the string contains valid Basic Text.
     ///
@@ -638,6 +1131,16 @@ impl TextStr {
         Self::from_text_unchecked(str::from_utf8_unchecked(b))
     }
 
+    /// Returns an empty text string slice.
+    ///
+    /// Unlike `<&TextStr>::default()`, this can be used in `const` contexts.
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> &'static Self {
+        let ptr: *const str = "";
+        unsafe { &*(ptr as *const Self) }
+    }
+
     /// Converts a string slice to a text string slice without checking that
     /// the string contains valid Basic Text.
     ///
@@ -646,9 +1149,11 @@ impl TextStr {
     /// This function is unsafe because it does not check that the bytes passed
     /// to it are valid Basic Text. If this constraint is violated, undefined
     /// behavior results, as the rest of this crate assumes that `&TextStr`s
-    /// are valid Basic Text.
+    /// are valid Basic Text. In debug builds, this is checked with a
+    /// `debug_assert!`.
     #[inline]
     pub unsafe fn from_text_unchecked(s: &str) -> &Self {
+        debug_assert!(is_basic_text(s), "invalid Basic Text: {:?}", s);
         let ptr: *const str = s;
         &*(ptr as *const Self)
     }
@@ -675,9 +1180,11 @@ impl TextStr {
     /// This function is unsafe because it does not check that the string
     /// passed to it is valid Basic Text. If this constraint is violated,
     /// undefined behavior results, as the rest of this crate assumes that
-    /// `&TextStr`s are valid Basic Text.
+    /// `&TextStr`s are valid Basic Text. In debug builds, this is checked
+    /// with a `debug_assert!`.
     #[inline]
     pub unsafe fn from_text_unchecked_mut(s: &mut str) -> &mut Self {
+        debug_assert!(is_basic_text(s), "invalid Basic Text: {:?}", s);
         let ptr: *mut str = s;
         &mut *(ptr as *mut Self)
     }
@@ -724,7 +1231,75 @@ impl TextStr {
         self.0.is_empty()
     }
 
-    // TODO: is_char_boundary?
+    /// Checks that `index`-th byte is the first byte in a UTF-8 code point
+    /// sequence or the end of the string.
+    #[inline]
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        self.0.is_char_boundary(index)
+    }
+
+    /// Checks that `index`-th byte is the first byte of an extended grapheme
+    /// cluster or the end of the string.
+    ///
+    /// This is stronger than `is_char_boundary`; splitting a text string
+    /// slice within a grapheme cluster may not produce valid Basic Text.
+    #[inline]
+    pub fn is_grapheme_boundary(&self, index: usize) -> bool {
+        self.is_char_boundary(index)
+            && GraphemeCursor::new(index, self.0.len(), true)
+                .is_boundary(&self.0, 0)
+                .unwrap()
+    }
+
+    /// Returns the largest index less than or equal to `byte` at which
+    /// [`split_at`] can divide the text string slice into two valid halves.
+    ///
+    /// Such an index is a grapheme cluster boundary where the preceding
+    /// scalar value can end Basic Text and the following one can start it.
+    /// Indices past the end are treated as the end.
+    ///
+    /// [`split_at`]: Self::split_at
+    #[inline]
+    pub fn floor_split_point(&self, byte: usize) -> usize {
+        let mut index = min(byte, self.0.len());
+        while !self.is_split_point(index) {
+            index -= 1;
+        }
+        index
+    }
+
+    /// Returns the smallest index greater than or equal to `byte` at which
+    /// [`split_at`] can divide the text string slice into two valid halves.
+    ///
+    /// See [`floor_split_point`] for the conditions on the index. Indices
+    /// past the end are treated as the end.
+    ///
+    /// [`split_at`]: Self::split_at
+    /// [`floor_split_point`]: Self::floor_split_point
+    #[inline]
+    pub fn ceil_split_point(&self, byte: usize) -> usize {
+        let mut index = min(byte, self.0.len());
+        while !self.is_split_point(index) {
+            index += 1;
+        }
+        index
+    }
+
+    /// Test whether `split_at(index)` would produce two valid halves.
+    fn is_split_point(&self, index: usize) -> bool {
+        if index == 0 || index == self.0.len() {
+            return true;
+        }
+        self.is_grapheme_boundary(index)
+            && self.0[..index]
+                .chars()
+                .next_back()
+                .is_some_and(is_basic_text_end)
+            && self.0[index..]
+                .chars()
+                .next()
+                .is_some_and(is_basic_text_start)
+    }
 
     /// Converts a text string slice to a byte slice.
     #[inline]
@@ -732,6 +1307,22 @@ impl TextStr {
         self.0.as_bytes()
     }
 
+    /// Tests whether the bytes of this text string slice are equal to
+    /// `bytes`, without validating `bytes`.
+    #[inline]
+    pub fn bytes_eq(&self, bytes: &[u8]) -> bool {
+        self.0.as_bytes() == bytes
+    }
+
+    /// Tests whether the bytes of this text string slice start with `bytes`,
+    /// without validating `bytes`.
+    ///
+    /// `bytes` needn't end on a scalar value boundary.
+    #[inline]
+    pub fn starts_with_bytes(&self, bytes: &[u8]) -> bool {
+        self.0.as_bytes().starts_with(bytes)
+    }
+
     /// Converts a mutable text string slice to a mutable byte slice.
     ///
     /// # Safety
@@ -807,7 +1398,120 @@ impl TextStr {
         self.0.bytes()
     }
 
-    // TODO: split*?
+    /// Returns an iterator over the extended grapheme clusters of a text
+    /// string slice.
+    #[inline]
+    pub fn graphemes(&self) -> Graphemes<'_> {
+        self.0.graphemes(true)
+    }
+
+    /// Returns an iterator over the extended grapheme clusters of a text
+    /// string slice, as text substring slices, and their positions.
+    ///
+    /// To find the byte offset of the grapheme cluster with index `n`, use
+    /// `grapheme_indices().nth(n)`.
+    #[inline]
+    pub fn grapheme_indices(&self) -> impl Iterator<Item = (usize, &TextSubstr)> {
+        self.0
+            .grapheme_indices(true)
+            .map(|(i, g)| (i, unsafe { TextSubstr::from_text_unchecked(g) }))
+    }
+
+    /// Returns an iterator over the extended grapheme clusters of a text
+    /// string slice, as text substring slices, starting from the end.
+    ///
+    /// This uses the same segmentation as [`graphemes`], so combining marks
+    /// and ZWJ sequences are kept together with their base.
+    ///
+    /// [`graphemes`]: Self::graphemes
+    #[inline]
+    pub fn graphemes_rev(&self) -> impl Iterator<Item = &TextSubstr> {
+        self.0
+            .graphemes(true)
+            .rev()
+            .map(|g| unsafe { TextSubstr::from_text_unchecked(g) })
+    }
+
+    /// Returns an iterator over the `char`s of a text string slice, with
+    /// their byte positions and the index of the extended grapheme cluster
+    /// each one belongs to.
+    ///
+    /// Combining marks and other scalar values which extend a grapheme
+    /// cluster have the same grapheme index as the base they're attached to.
+    #[inline]
+    pub fn char_grapheme_indices(&self) -> impl Iterator<Item = (usize, char, usize)> + '_ {
+        self.0
+            .grapheme_indices(true)
+            .enumerate()
+            .flat_map(|(grapheme, (start, g))| {
+                g.char_indices().map(move |(i, c)| (start + i, c, grapheme))
+            })
+    }
+
+    /// Returns the index of the extended grapheme cluster containing the byte
+    /// at offset `byte`.
+    ///
+    /// An offset equal to the length of the text string slice returns the
+    /// number of extended grapheme clusters, which is the position of a
+    /// cursor at the end. Returns `None` if `byte` is greater than that.
+    #[inline]
+    pub fn byte_to_grapheme(&self, byte: usize) -> Option<usize> {
+        match byte.cmp(&self.0.len()) {
+            Ordering::Less => Some(
+                self.grapheme_indices()
+                    .take_while(|(i, _)| *i <= byte)
+                    .count()
+                    - 1,
+            ),
+            Ordering::Equal => Some(self.grapheme_count()),
+            Ordering::Greater => None,
+        }
+    }
+
+    /// Returns the number of extended grapheme clusters in a text string
+    /// slice.
+    #[inline]
+    pub fn grapheme_count(&self) -> usize {
+        self.graphemes().count()
+    }
+
+    /// Returns an estimate of the number of columns a text string slice
+    /// occupies when displayed in a terminal.
+    ///
+    /// East Asian Wide and Fullwidth characters and emoji, including ZWJ
+    /// sequences, are two columns wide, and combining marks are zero columns
+    /// wide. Newlines are zero columns wide, so for text with multiple lines,
+    /// this is the sum of the widths of the lines.
+    #[inline]
+    pub fn display_width(&self) -> usize {
+        self.graphemes()
+            .map(|g| if g == "\n" { 0 } else { g.width() })
+            .sum()
+    }
+
+    /// An iterator over the pieces of a text string slice separated by
+    /// whitespace, as text substring slices.
+    #[inline]
+    pub fn split_whitespace(&self) -> impl Iterator<Item = &TextSubstr> {
+        self.0
+            .split_whitespace()
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over the lines of a text string, as text substring
+    /// slices, including their trailing '\n's.
+    ///
+    /// Unlike [`lines`], the final line is yielded without a '\n' if the
+    /// string doesn't end with one, so concatenating the lines reproduces the
+    /// string exactly. An empty string yields no lines.
+    ///
+    /// [`lines`]: Self::lines
+    #[inline]
+    pub fn lines_with_endings(&self) -> impl Iterator<Item = &TextSubstr> {
+        self.0
+            .split_inclusive('\n')
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
 
     /// An iterator over the lines of a text string, as text string slices.
     ///
@@ -940,66 +1644,203 @@ impl TextStr {
         self.0.rfind(pat)
     }
 
-    // TODO: *split*?
-
-    /// An iterator over the disjoint matches of a pattern within the given
-    /// text string slice.
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices.
     #[cfg(pattern)]
     #[inline]
-    pub fn matches<'a, P>(&'a self, pat: P) -> Matches<'a, P>
+    pub fn split<'a, P>(&'a self, pat: P) -> impl Iterator<Item = &'a TextSubstr>
     where
         P: Pattern<'a>,
     {
-        self.0.matches(pat)
+        self.0
+            .split(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
     }
 
-    /// An iterator over the disjoint matches of a pattern within the given
-    /// text string slice.
-    ///
-    /// TODO: There should be a `TextMatches` which yields `&TextStr`s.
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices.
     #[cfg(not(pattern))]
     #[inline]
-    pub fn matches<'a>(&'a self, pat: &'a str) -> Matches<'a, &str> {
-        self.0.matches(pat)
+    pub fn split<'a>(&'a self, pat: &'a str) -> impl Iterator<Item = &'a TextSubstr> {
+        self.0
+            .split(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
     }
 
-    /// An iterator over the disjoint matches of a pattern within this
-    /// text string slice, yielded in reverse order.
-    ///
-    /// TODO: There should be a `TextRMatches` which yields `&TextStr`s.
+    /// An iterator over at most `n` pieces of this text string slice
+    /// separated by matches of a pattern, as text substring slices. The last
+    /// piece contains the remainder of the slice.
     #[cfg(pattern)]
     #[inline]
-    pub fn rmatches<'a, P>(&'a self, pat: P) -> RMatches<'a, P>
+    pub fn splitn<'a, P>(&'a self, n: usize, pat: P) -> impl Iterator<Item = &'a TextSubstr>
     where
         P: Pattern<'a>,
-        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
     {
-        self.0.rmatches(pat)
+        self.0
+            .splitn(n, pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
     }
 
-    /// An iterator over the disjoint matches of a pattern within this
-    /// text string slice, yielded in reverse order.
+    /// An iterator over at most `n` pieces of this text string slice
+    /// separated by matches of a pattern, as text substring slices. The last
+    /// piece contains the remainder of the slice.
     #[cfg(not(pattern))]
     #[inline]
-    pub fn rmatches<'a>(&'a self, pat: &'a str) -> RMatches<'a, &'a str> {
-        self.0.rmatches(pat)
+    pub fn splitn<'a>(&'a self, n: usize, pat: &'a str) -> impl Iterator<Item = &'a TextSubstr> {
+        self.0
+            .splitn(n, pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
     }
 
-    /// An iterator over the disjoint matches of a pattern within this
-    /// text string slice as well as the index that the match starts at.
-    ///
-    /// TODO: There should be a `TextMatchIndices` which yields `&TextStr`s.
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices, yielded in reverse
+    /// order.
     #[cfg(pattern)]
     #[inline]
-    pub fn match_indices<'a, P>(&'a self, pat: P) -> MatchIndices<'a, P>
+    pub fn rsplit<'a, P>(&'a self, pat: P) -> impl Iterator<Item = &'a TextSubstr>
     where
         P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
     {
-        self.0.match_indices(pat)
+        self.0
+            .rsplit(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
     }
 
-    /// An iterator over the disjoint matches of a pattern within this
-    /// text string slice as well as the index that the match starts at.
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices, yielded in reverse
+    /// order.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn rsplit<'a>(&'a self, pat: &'a str) -> impl Iterator<Item = &'a TextSubstr> {
+        self.0
+            .rsplit(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Splits this text string slice on the first occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn split_once<'a, P>(&'a self, pat: P) -> Option<(&'a TextSubstr, &'a TextSubstr)>
+    where
+        P: Pattern<'a>,
+    {
+        self.0.split_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    /// Splits this text string slice on the first occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn split_once<'a>(&'a self, pat: &str) -> Option<(&'a TextSubstr, &'a TextSubstr)> {
+        self.0.split_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    /// Splits this text string slice on the last occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn rsplit_once<'a, P>(&'a self, pat: P) -> Option<(&'a TextSubstr, &'a TextSubstr)>
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        self.0.rsplit_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    /// Splits this text string slice on the last occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn rsplit_once<'a>(&'a self, pat: &str) -> Option<(&'a TextSubstr, &'a TextSubstr)> {
+        self.0.rsplit_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    // TODO: rsplitn, split_terminator, rsplit_terminator?
+
+    /// An iterator over the disjoint matches of a pattern within the given
+    /// text string slice.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn matches<'a, P>(&'a self, pat: P) -> Matches<'a, P>
+    where
+        P: Pattern<'a>,
+    {
+        self.0.matches(pat)
+    }
+
+    /// An iterator over the disjoint matches of a pattern within the given
+    /// text string slice.
+    ///
+    /// TODO: There should be a `TextMatches` which yields `&TextStr`s.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn matches<'a>(&'a self, pat: &'a str) -> Matches<'a, &str> {
+        self.0.matches(pat)
+    }
+
+    /// An iterator over the disjoint matches of a pattern within this
+    /// text string slice, yielded in reverse order.
+    ///
+    /// TODO: There should be a `TextRMatches` which yields `&TextStr`s.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn rmatches<'a, P>(&'a self, pat: P) -> RMatches<'a, P>
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        self.0.rmatches(pat)
+    }
+
+    /// An iterator over the disjoint matches of a pattern within this
+    /// text string slice, yielded in reverse order.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn rmatches<'a>(&'a self, pat: &'a str) -> RMatches<'a, &'a str> {
+        self.0.rmatches(pat)
+    }
+
+    /// An iterator over the disjoint matches of a pattern within this
+    /// text string slice as well as the index that the match starts at.
+    ///
+    /// TODO: There should be a `TextMatchIndices` which yields `&TextStr`s.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn match_indices<'a, P>(&'a self, pat: P) -> MatchIndices<'a, P>
+    where
+        P: Pattern<'a>,
+    {
+        self.0.match_indices(pat)
+    }
+
+    /// An iterator over the disjoint matches of a pattern within this
+    /// text string slice as well as the index that the match starts at.
     #[cfg(not(pattern))]
     #[inline]
     pub fn match_indices<'a>(&'a self, pat: &'a str) -> MatchIndices<'a, &'a str> {
@@ -1047,8 +1888,119 @@ impl TextStr {
         unsafe { Self::from_text_unchecked(self.0.trim_end()) }
     }
 
-    // TODO: trim_matches, trim_start_matches, strip_prefix, strip_suffix,
-    // trim_end_matches?
+    /// Returns a text string slice with all prefixes and suffixes that match
+    /// a pattern repeatedly removed.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn trim_matches<'a, P>(&'a self, pat: P) -> &'a TextSubstr
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: DoubleEndedSearcher<'a>,
+    {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all prefixes and suffixes that match
+    /// a pattern repeatedly removed.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn trim_matches(&self, pat: char) -> &TextSubstr {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all prefixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn trim_start_matches<'a, P>(&'a self, pat: P) -> &'a TextSubstr
+    where
+        P: Pattern<'a>,
+    {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_start_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all prefixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn trim_start_matches<'a>(&'a self, pat: &str) -> &'a TextSubstr {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_start_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all suffixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn trim_end_matches<'a, P>(&'a self, pat: P) -> &'a TextSubstr
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_end_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all suffixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn trim_end_matches<'a>(&'a self, pat: &str) -> &'a TextSubstr {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_end_matches(pat)) }
+    }
+
+    /// Returns a text string slice with the prefix removed.
+    ///
+    /// If the string starts with the pattern `prefix`, returns the substring
+    /// after the prefix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn strip_prefix<'a, P>(&'a self, prefix: P) -> Option<&'a TextSubstr>
+    where
+        P: Pattern<'a>,
+    {
+        self.0
+            .strip_prefix(prefix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Returns a text string slice with the prefix removed.
+    ///
+    /// If the string starts with the pattern `prefix`, returns the substring
+    /// after the prefix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn strip_prefix<'a>(&'a self, prefix: &str) -> Option<&'a TextSubstr> {
+        self.0
+            .strip_prefix(prefix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Returns a text string slice with the suffix removed.
+    ///
+    /// If the string ends with the pattern `suffix`, returns the substring
+    /// before the suffix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn strip_suffix<'a, P>(&'a self, suffix: P) -> Option<&'a TextSubstr>
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        self.0
+            .strip_suffix(suffix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Returns a text string slice with the suffix removed.
+    ///
+    /// If the string ends with the pattern `suffix`, returns the substring
+    /// before the suffix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn strip_suffix<'a>(&'a self, suffix: &str) -> Option<&'a TextSubstr> {
+        self.0
+            .strip_suffix(suffix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
 
     /// Parses this text string slice into another type.
     #[inline]
@@ -1061,6 +2013,37 @@ impl TextStr {
         self.0.parse()
     }
 
+    /// Checks if this text string contains any default-ignorable code
+    /// points, such as ZWJ, ZWSP, variation selectors, or tag characters.
+    ///
+    /// CGJs inserted to make this a valid Basic Text string count too.
+    #[inline]
+    pub fn contains_default_ignorable(&self) -> bool {
+        self.0.chars().any(is_default_ignorable)
+    }
+
+    /// Returns a copy of this text string with its default-ignorable code
+    /// points removed, renormalized as needed.
+    ///
+    /// A ZWJ between two emoji, optionally with an emoji modifier or U+FE0F
+    /// (VS16) before the ZWJ, is kept, so that emoji ZWJ sequences such as
+    /// "👨\u{200d}💻" are preserved. Variation selectors are removed, so
+    /// emoji presentation sequences lose their VS16, and tag sequences lose
+    /// their tags. CGJs are removed and then reinserted where Basic Text
+    /// requires them.
+    pub fn strip_default_ignorables(&self) -> TextString {
+        let chars = self.0.chars().collect::<Vec<_>>();
+        let kept = chars
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| !is_default_ignorable(**c) || is_emoji_zwj(&chars, *i))
+            .map(|(_, c)| *c);
+        let mut s = StreamSafeNfc::new(kept).collect::<String>();
+        guard_start(&mut s);
+        guard_end(&mut s);
+        TextString(s)
+    }
+
     /// Checks if all characters in this text string are within the ASCII
     /// range.
     #[inline]
@@ -1074,6 +2057,50 @@ impl TextStr {
         self.0.eq_ignore_ascii_case(&other.0)
     }
 
+    /// Checks that two text strings are a caseless match, using Unicode full
+    /// case folding followed by NFC, so that "stra\u{df}e" matches "STRASSE".
+    ///
+    /// This is Unicode default caseless matching, which isn't tailored for
+    /// any locale. In particular, Turkish and Azerbaijani dotted and dotless
+    /// i's don't match the way speakers of those languages would expect;
+    /// "I" matches "i", but not "\u{131}".
+    pub fn eq_ignore_case(&self, other: &Self) -> bool {
+        self.0
+            .chars()
+            .default_case_fold()
+            .nfc()
+            .eq(other.0.chars().default_case_fold().nfc())
+    }
+
+    /// Checks that two text strings are equal line by line, ignoring
+    /// whitespace at the end of each line.
+    ///
+    /// Lines are split as with [`str::lines`], so a missing newline at the
+    /// end is also ignored, though trailing empty lines are not. Since Basic
+    /// Text normalizes all newlines to '\n', text that originally used
+    /// different line endings compares equal.
+    pub fn eq_ignore_trailing_whitespace(&self, other: &Self) -> bool {
+        self.0
+            .lines()
+            .map(str::trim_end)
+            .eq(other.0.lines().map(str::trim_end))
+    }
+
+    /// Feeds the canonical Basic Text form of `self` into `state`.
+    ///
+    /// A `TextStr` is always in its canonical form, so this is the same as
+    /// its `Hash` implementation; text which differed only in ways that the
+    /// Basic Text translation removes, such as "\u{c5}" and "A\u{30a}",
+    /// hashes the same once translated. To hash a `&str` consistently with
+    /// this, translate it with [`TextString::from_text_lossy`] first, or use
+    /// [`NormalizedKey`].
+    ///
+    /// [`NormalizedKey`]: crate::NormalizedKey
+    #[inline]
+    pub fn normalized_hash<H: Hasher>(&self, state: &mut H) {
+        self.hash(state);
+    }
+
     /// Converts a `Box<TextStr`> into a `Box<[u8]>` without copying or
     /// allocating.
     #[inline]
@@ -1105,7 +2132,19 @@ impl TextStr {
 
     /// Creates a new [`TextString`] by repeating a string `n` times.
     pub fn repeat(&self, n: usize) -> TextString {
-        unsafe { TextString::from_text_vec_unchecked(self.as_bytes().repeat(n)) }
+        let mut s = self.0.repeat(n);
+
+        // The end of one copy may compose with the start of the next, so
+        // renormalize unless the seam is trivially safe.
+        if n > 1 {
+            if let (Some(first), Some(last)) = (self.0.chars().next(), self.0.chars().next_back()) {
+                if last != '\n' && !(last.is_ascii() && first.is_ascii()) {
+                    renormalize(&mut s);
+                }
+            }
+        }
+
+        unsafe { TextString::from_text_unchecked(s) }
     }
 
     /// Return an iterator that escapes each `char` in `self` with
@@ -1129,6 +2168,53 @@ impl TextStr {
         self.0.escape_unicode()
     }
 
+    /// Return a copy of `self` quoted for display in a terminal, as a single
+    /// line in double quotes.
+    ///
+    /// Basic Text already excludes escape sequences, control codes other
+    /// than '\n' and '\t', and explicit bidirectional formatting characters,
+    /// so a `TextStr` can't change the state of a terminal. This additionally
+    /// replaces '\n' and '\t' with "\\n" and "\\t", and escapes '\\' and '"'
+    /// with a backslash, so that the result occupies a single line and its
+    /// extent is unambiguous. A CGJ is inserted after an escape which is
+    /// followed by a non-starter, so that it doesn't combine with the escape.
+    ///
+    /// This is not quoting for a shell; shells interpret other characters
+    /// within double quotes, such as '$'.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::text;
+    ///
+    /// let s = text!("say \"hi\"\tto\nme");
+    /// assert_eq!(s.quote_for_terminal(), "\"say \\\"hi\\\"\\tto\\nme\"");
+    /// ```
+    pub fn quote_for_terminal(&self) -> TextString {
+        let mut s = String::with_capacity(self.0.len() + 2);
+        s.push('"');
+        let mut escaped = false;
+        for c in self.0.chars() {
+            if escaped && !is_basic_text_start(c) {
+                s.push(CGJ);
+            }
+            escaped = false;
+            match c {
+                '\n' => s.push_str("\\n"),
+                '\t' => s.push_str("\\t"),
+                '\\' => s.push_str("\\\\"),
+                '"' => s.push_str("\\\""),
+                c => {
+                    s.push(c);
+                    continue;
+                }
+            }
+            escaped = true;
+        }
+        s.push('"');
+        unsafe { TextString::from_text_unchecked(s) }
+    }
+
     // TODO: make_ascii_uppercase, make_ascii_lowercase, replace*,
     // to_lowercase, to_uppercase, to_ascii_uppercase, to_ascii_lowercase;
     // determine whether these can be done without breaking NFC.
@@ -1349,6 +2435,103 @@ impl From<&'_ TextStr> for TextString {
     }
 }
 
+impl From<TextString> for String {
+    #[inline]
+    fn from(s: TextString) -> Self {
+        s.into_string()
+    }
+}
+
+impl From<TextString> for OsString {
+    #[inline]
+    fn from(s: TextString) -> Self {
+        s.into_string().into()
+    }
+}
+
+impl From<TextString> for PathBuf {
+    #[inline]
+    fn from(s: TextString) -> Self {
+        s.into_string().into()
+    }
+}
+
+impl From<TextString> for Cow<'static, str> {
+    #[inline]
+    fn from(s: TextString) -> Self {
+        Cow::Owned(s.into_string())
+    }
+}
+
+impl<'a> From<&'a TextStr> for Cow<'a, str> {
+    #[inline]
+    fn from(s: &'a TextStr) -> Self {
+        Cow::Borrowed(s.as_str())
+    }
+}
+
+impl TryFrom<char> for TextString {
+    type Error = BasicTextError;
+
+    #[inline]
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        check_basic_text_char(c)?;
+        if c == ESC {
+            return Err(BasicTextError::Escape);
+        }
+        if !is_basic_text_start(c) {
+            return Err(BasicTextError::NonStarter(c));
+        }
+        if !is_basic_text_end(c) {
+            return Err(BasicTextError::NonEnding(c));
+        }
+
+        let mut s = String::from(c);
+        renormalize(&mut s);
+        Ok(unsafe { Self::from_text_unchecked(s) })
+    }
+}
+
+impl<'a> TryFrom<&'a str> for &'a TextStr {
+    type Error = TextError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        TextStr::from_text(s)
+    }
+}
+
+impl TryFrom<String> for TextString {
+    type Error = FromTextError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_text(s)
+    }
+}
+
+/// Parse a `TextString` from a `str`.
+///
+/// The input must already be valid Basic Text; it is not repaired. To repair
+/// arbitrary input, use [`TextString::from_text_lossy`].
+impl FromStr for TextString {
+    type Err = FromTextError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_text(s.to_owned())
+    }
+}
+
+impl TryFrom<Vec<u8>> for TextString {
+    type Error = FromTextError;
+
+    #[inline]
+    fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_text_vec(vec)
+    }
+}
+
 impl From<Cow<'_, TextStr>> for Box<TextStr> {
     #[inline]
     fn from(cow: Cow<'_, TextStr>) -> Self {
@@ -1392,14 +2575,15 @@ impl From<&TextStr> for Box<TextStr> {
 impl<'a> arbitrary::Arbitrary<'a> for &'a TextStr {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
         let size = u.arbitrary_len::<u8>()?;
-        match TextStr::from_text_bytes(&u.peek_bytes(size).unwrap()) {
+        let bytes = u.peek_bytes(size).ok_or(arbitrary::Error::NotEnoughData)?;
+        match TextStr::from_text_bytes(bytes) {
             Ok(s) => {
-                u.bytes(size).unwrap();
+                u.bytes(size)?;
                 Ok(s)
             }
             Err(e) => {
                 let i = e.valid_up_to();
-                let valid = u.bytes(i).unwrap();
+                let valid = u.bytes(i)?;
                 let s = unsafe {
                     debug_assert!(TextStr::from_text_bytes(valid).is_ok());
                     TextStr::from_text_bytes_unchecked(valid)
@@ -1511,14 +2695,69 @@ pub fn default_read_to_text_string<Inner: ReadText + ?Sized>(
     Ok(n)
 }
 
-#[test]
-fn normalize_string() {
-    let ring = "\u{30a}";
-    let unnormal = "A\u{30a}";
-    let unnormal_nl = "A\u{30a}\n";
-    let composed = TextStr::from_text("\u{c5}").unwrap();
-    let composed_nl = TextStr::from_text("\u{c5}\n").unwrap();
-
+/// Concatenate `parts`, placing `sep` between each adjacent pair.
+///
+/// Unlike concatenating the bytes, this renormalizes the result, since
+/// scalar values on either side of a seam may compose with each other.
+///
+/// # Examples
+///
+/// ```rust
+/// use basic_text::{join, TextStr};
+///
+/// let sep = TextStr::from_text(", ").unwrap();
+/// let parts = ["a", "b", "c"].map(|s| TextStr::from_text(s).unwrap());
+/// assert_eq!(join(sep, &parts), "a, b, c");
+/// ```
+pub fn join(sep: &TextStr, parts: &[&TextStr]) -> TextString {
+    let mut s = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i != 0 {
+            s.push_str(&sep.0);
+        }
+        s.push_str(&part.0);
+    }
+
+    renormalize(&mut s);
+    guard_start(&mut s);
+    guard_end(&mut s);
+
+    unsafe { TextString::from_text_unchecked(s) }
+}
+
+/// Converts an `OsStr`, such as a command-line argument or an environment
+/// variable value, to a `TextString`, failing if it isn't valid Basic Text.
+///
+/// On platforms where `OsStr` is not byte-oriented, such as Windows, the
+/// bytes in the error are the platform's internal encoding of `s`.
+#[inline]
+pub fn from_os_str(s: &OsStr) -> Result<TextString, FromTextError> {
+    TextString::from_text_vec(s.as_encoded_bytes().to_vec())
+}
+
+/// Converts an `OsStr`, such as a command-line argument or an environment
+/// variable value, to a `TextString`, replacing invalid sequences with
+/// U+FFFD and repairing the result in the same way as
+/// [`TextString::from_text_lossy`].
+///
+/// On Windows, each unpaired surrogate is replaced by one U+FFFD, as with
+/// [`TextString::from_utf16_lossy`]. On byte-oriented platforms, invalid
+/// UTF-8, including UTF-8-style encodings of surrogates, is replaced as with
+/// [`String::from_utf8_lossy`], which produces one U+FFFD per maximal
+/// invalid subsequence.
+#[inline]
+pub fn from_os_str_lossy(s: &OsStr) -> TextString {
+    TextString::from_text_lossy(&s.to_string_lossy()).into_owned()
+}
+
+#[test]
+fn normalize_string() {
+    let ring = "\u{30a}";
+    let unnormal = "A\u{30a}";
+    let unnormal_nl = "A\u{30a}\n";
+    let composed = TextStr::from_text("\u{c5}").unwrap();
+    let composed_nl = TextStr::from_text("\u{c5}\n").unwrap();
+
     assert_eq!(TextStr::from_text(unnormal).unwrap_err().valid_up_to(), 1);
     assert_eq!(TextStr::from_text(ring).unwrap_err().valid_up_to(), 0);
     assert_eq!(composed, &TextString::from_text_lossy(unnormal));
@@ -1534,6 +2773,543 @@ fn validate_string() {
     );
 }
 
+#[test]
+fn graphemes() {
+    let s = TextStr::from_text("x\u{301}🐕\u{200d}🦺漢x\n").unwrap();
+    assert_eq!(
+        s.graphemes().collect::<Vec<_>>(),
+        ["x\u{301}", "🐕\u{200d}🦺", "漢", "x", "\n"]
+    );
+    assert_eq!(s.grapheme_count(), 5);
+    assert_eq!(s.display_width(), 6);
+    assert_eq!(TextStr::from_text("").unwrap().display_width(), 0);
+    assert_eq!(TextStr::from_text("ab\ncd\n").unwrap().display_width(), 4);
+}
+
+#[test]
+fn split_points() {
+    let s = TextStr::from_text("x\u{301}🐕\u{200d}🦺\u{34f}\u{301}漢").unwrap();
+    assert_eq!(s.floor_split_point(0), 0);
+    assert_eq!(s.floor_split_point(2), 0);
+    assert_eq!(s.ceil_split_point(2), 3);
+    assert_eq!(s.floor_split_point(8), 3);
+    assert_eq!(s.ceil_split_point(8), 18);
+    assert_eq!(s.floor_split_point(20), 18);
+    assert_eq!(s.ceil_split_point(20), 21);
+    assert_eq!(s.floor_split_point(100), 21);
+    assert_eq!(s.ceil_split_point(100), 21);
+
+    for byte in 0..=s.len() {
+        let floor = s.floor_split_point(byte);
+        let ceil = s.ceil_split_point(byte);
+        assert!(floor <= byte && byte <= ceil);
+        s.split_at(floor);
+        s.split_at(ceil);
+    }
+}
+
+#[test]
+fn char_grapheme_indices() {
+    let s = TextStr::from_text("x\u{301}🐕\u{200d}🦺a\n").unwrap();
+    assert_eq!(
+        s.char_grapheme_indices().collect::<Vec<_>>(),
+        [
+            (0, 'x', 0),
+            (1, '\u{301}', 0),
+            (3, '🐕', 1),
+            (7, '\u{200d}', 1),
+            (10, '🦺', 1),
+            (14, 'a', 2),
+            (15, '\n', 3),
+        ]
+    );
+    assert_eq!(
+        TextStr::from_text("")
+            .unwrap()
+            .char_grapheme_indices()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn graphemes_rev() {
+    let s = TextStr::from_text("x\u{301}🐕\u{200d}🦺漢x\u{323}\u{301}\n").unwrap();
+    assert_eq!(
+        s.graphemes_rev()
+            .map(TextSubstr::as_str)
+            .collect::<Vec<_>>(),
+        ["\n", "x\u{323}\u{301}", "漢", "🐕\u{200d}🦺", "x\u{301}"]
+    );
+    assert_eq!(TextStr::from_text("").unwrap().graphemes_rev().count(), 0);
+}
+
+#[test]
+fn grapheme_indices() {
+    let s = TextStr::from_text("x\u{301}🐕\u{200d}🦺漢\n").unwrap();
+    assert_eq!(
+        s.grapheme_indices()
+            .map(|(i, g)| (i, g.as_str()))
+            .collect::<Vec<_>>(),
+        [(0, "x\u{301}"), (3, "🐕\u{200d}🦺"), (14, "漢"), (17, "\n")]
+    );
+    assert_eq!(s.byte_to_grapheme(0), Some(0));
+    assert_eq!(s.byte_to_grapheme(1), Some(0));
+    assert_eq!(s.byte_to_grapheme(3), Some(1));
+    assert_eq!(s.byte_to_grapheme(13), Some(1));
+    assert_eq!(s.byte_to_grapheme(14), Some(2));
+    assert_eq!(s.byte_to_grapheme(17), Some(3));
+    assert_eq!(s.byte_to_grapheme(18), Some(4));
+    assert_eq!(s.byte_to_grapheme(19), None);
+    assert_eq!(s.grapheme_indices().nth(2).map(|(i, _)| i), Some(14));
+}
+
+#[test]
+fn boundaries() {
+    let s = TextStr::from_text("x\u{301}🐕\u{200d}🦺\n").unwrap();
+    assert!(s.is_char_boundary(0));
+    assert!(s.is_char_boundary(1));
+    assert!(!s.is_char_boundary(2));
+    assert!(s.is_char_boundary(7));
+    assert!(s.is_grapheme_boundary(0));
+    assert!(!s.is_grapheme_boundary(1));
+    assert!(!s.is_grapheme_boundary(2));
+    assert!(s.is_grapheme_boundary(3));
+    assert!(!s.is_grapheme_boundary(7));
+    assert!(s.is_grapheme_boundary(14));
+    assert!(s.is_grapheme_boundary(15));
+    assert!(!s.is_grapheme_boundary(16));
+}
+
+#[test]
+fn check_invariant() {
+    TextStr::from_text("").unwrap().check_invariant().unwrap();
+    TextStr::from_text("\u{34f}\u{301}x\n")
+        .unwrap()
+        .check_invariant()
+        .unwrap();
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "invalid Basic Text")]
+fn from_text_unchecked_invalid() {
+    let _ = unsafe { TextStr::from_text_unchecked("\u{301}") };
+}
+
+#[test]
+fn from_substrs() {
+    let substr = |s| TextSubstr::from_text(s).unwrap();
+    assert_eq!(TextString::from_substrs([]).unwrap(), "");
+    assert_eq!(
+        TextString::from_substrs([substr("hello "), substr("world\n")]).unwrap(),
+        "hello world\n"
+    );
+    assert_eq!(
+        TextString::from_substrs([substr("A"), substr("\u{30a}")]).unwrap(),
+        "\u{c5}"
+    );
+    assert_eq!(
+        TextString::from_substrs([substr("\u{30a}"), substr("x")]).unwrap(),
+        "\u{34f}\u{30a}x"
+    );
+    assert_eq!(
+        TextString::from_substrs([substr("x\u{200d}")]).unwrap(),
+        "x\u{200d}\u{34f}"
+    );
+}
+
+#[test]
+fn split_off() {
+    let mut s = TextString::from_text("🐕\u{200d}🦺\n".to_owned()).unwrap();
+    let tail = s.split_off(7);
+    assert_eq!(s, "🐕\u{200d}\u{34f}");
+    assert_eq!(tail.as_str(), "🦺\n");
+
+    let mut s = TextString::from_text("🐕\u{200d}🦺\n".to_owned()).unwrap();
+    let tail = s.split_off(4);
+    assert_eq!(s, "🐕");
+    assert_eq!(tail.as_str(), "\u{200d}🦺\n");
+
+    let mut s = TextString::from_text("x\u{301}\n".to_owned()).unwrap();
+    let tail = s.split_off(1);
+    assert_eq!(s, "x");
+    assert_eq!(tail.as_str(), "\u{301}\n");
+
+    let mut s = TextString::from_text("hello".to_owned()).unwrap();
+    assert_eq!(s.split_off(5).as_str(), "");
+    assert_eq!(s, "hello");
+}
+
+#[test]
+fn retain() {
+    // "A\u{30a}" is normalized to "\u{c5}", so there's no base to remove.
+    let mut s = TextString::from_text("A\u{30a}\n".to_owned()).unwrap();
+    assert_eq!(s, "\u{c5}\n");
+    s.retain(|c| c != 'A');
+    assert_eq!(s, "\u{c5}\n");
+
+    // Removing the base strands the combining mark, so it's guarded.
+    let mut s = TextString::from_text("x\u{30a}y\n".to_owned()).unwrap();
+    s.retain(|c| c != 'x');
+    assert_eq!(s, "\u{34f}\u{30a}y\n");
+
+    // Removing a CGJ lets its neighbors compose.
+    let mut s = TextString::from_text("A\u{34f}\u{30a}\n".to_owned()).unwrap();
+    s.retain(|c| c != '\u{34f}');
+    assert_eq!(s, "\u{c5}\n");
+
+    // Removing the end of a ZWJ sequence leaves a ZWJ, so it's guarded.
+    let mut s = TextString::from_text("🐕\u{200d}🦺".to_owned()).unwrap();
+    s.retain(|c| c != '🦺');
+    assert_eq!(s, "🐕\u{200d}\u{34f}");
+}
+
+#[test]
+fn replace_range() {
+    let mut s = TextString::from_text("hello world\n".to_owned()).unwrap();
+    s.replace_range(6..11, TextStr::from_text("there").unwrap());
+    assert_eq!(s, "hello there\n");
+
+    // Replacing the base of a combining mark lets the new base compose.
+    let mut s = TextString::from_text("x\u{30a}\n".to_owned()).unwrap();
+    s.replace_range(..1, TextStr::from_text("A").unwrap());
+    assert_eq!(s, "\u{c5}\n");
+
+    // Removing the base strands the combining mark, so it's guarded.
+    let mut s = TextString::from_text("x\u{30a}\n".to_owned()).unwrap();
+    s.replace_range(..1, TextStr::from_text("").unwrap());
+    assert_eq!(s, "\u{34f}\u{30a}\n");
+
+    // A replacement beginning with a guarded combining mark stays guarded.
+    let mut s = TextString::from_text("a\n".to_owned()).unwrap();
+    s.replace_range(1..1, TextStr::from_text("\u{34f}\u{301}").unwrap());
+    assert_eq!(s, "a\u{34f}\u{301}\n");
+
+    // Removing the middle lets the neighbors compose across the seam.
+    let mut s = TextString::from_text("\u{1100}x\u{1161}".to_owned()).unwrap();
+    s.replace_range(3..4, TextStr::from_text("").unwrap());
+    assert_eq!(s, "\u{ac00}");
+}
+
+#[test]
+#[should_panic]
+fn replace_range_non_char_boundary() {
+    let mut s = TextString::from_text("\u{c5}\n".to_owned()).unwrap();
+    s.replace_range(1..2, TextStr::from_text("").unwrap());
+}
+
+#[test]
+fn drain() {
+    let mut s = TextString::from_text("hello world\n".to_owned()).unwrap();
+    assert_eq!(s.drain(5..11).collect::<String>(), " world");
+    assert_eq!(s, "hello\n");
+
+    // Removing the base strands the combining mark, so it's guarded.
+    let mut s = TextString::from_text("x\u{30a}\n".to_owned()).unwrap();
+    assert_eq!(s.drain(..1).collect::<String>(), "x");
+    assert_eq!(s, "\u{34f}\u{30a}\n");
+
+    // Removing a CGJ lets its neighbors compose.
+    let mut s = TextString::from_text("A\u{34f}\u{30a}\n".to_owned()).unwrap();
+    assert_eq!(s.drain(1..3).collect::<String>(), "\u{34f}");
+    assert_eq!(s, "\u{c5}\n");
+
+    // The range is removed even if the iterator isn't consumed.
+    let mut s = TextString::from_text("hello\n".to_owned()).unwrap();
+    drop(s.drain(..));
+    assert!(s.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn drain_non_char_boundary() {
+    let mut s = TextString::from_text("\u{c5}\n".to_owned()).unwrap();
+    drop(s.drain(1..));
+}
+
+#[test]
+fn try_from() {
+    let t: &TextStr = "hello\n".try_into().unwrap();
+    assert_eq!(t, "hello\n");
+    assert!(<&TextStr>::try_from("\u{7}").is_err());
+
+    let t: TextString = String::from("hello\n").try_into().unwrap();
+    assert_eq!(t, "hello\n");
+    let err = TextString::try_from(String::from("\u{301}")).unwrap_err();
+    assert_eq!(err.into_bytes(), "\u{301}".as_bytes());
+
+    let t: TextString = b"hello\n".to_vec().try_into().unwrap();
+    assert_eq!(t, "hello\n");
+    assert!(TextString::try_from(b"\xff".to_vec()).is_err());
+}
+
+#[test]
+fn join_seams() {
+    fn text(s: &str) -> &TextStr {
+        TextStr::from_text(s).unwrap()
+    }
+
+    assert_eq!(join(text(", "), &[]), "");
+    assert_eq!(join(text(", "), &[text("a")]), "a");
+    assert_eq!(join(text(", "), &[text("a"), text("b")]), "a, b");
+    assert_eq!(join(text(""), &[text("a"), text("b")]), "ab");
+
+    // A part ending in a base followed by a part starting with a guarded
+    // combining mark: the CGJ keeps them from composing.
+    assert_eq!(
+        join(text(""), &[text("A"), text("\u{34f}\u{30a}")]),
+        "A\u{34f}\u{30a}"
+    );
+
+    // Hangul jamo on either side of a seam compose.
+    assert_eq!(
+        join(text(""), &[text("\u{1100}"), text("\u{1161}")]),
+        "\u{ac00}"
+    );
+    assert_eq!(
+        join(text("\u{1161}"), &[text("\u{1100}"), text("\u{1100}")]),
+        "\u{ac00}\u{1100}"
+    );
+}
+
+#[test]
+fn repeat_seams() {
+    fn text(s: &str) -> &TextStr {
+        TextStr::from_text(s).unwrap()
+    }
+
+    assert_eq!(text("ab").repeat(0), "");
+    assert_eq!(text("ab").repeat(3), "ababab");
+    assert_eq!(text("\u{e9}").repeat(2), "\u{e9}\u{e9}");
+    assert_eq!(text("\u{e9}\n").repeat(2), "\u{e9}\n\u{e9}\n");
+
+    // A guarded combining mark at the start stays guarded after a base.
+    assert_eq!(
+        text("\u{34f}\u{301}e").repeat(2),
+        "\u{34f}\u{301}e\u{34f}\u{301}e"
+    );
+
+    // Hangul jamo at the seam compose.
+    assert_eq!(
+        text("\u{1161}\u{1100}").repeat(2),
+        "\u{1161}\u{ac00}\u{1100}"
+    );
+    assert_eq!(text("\u{1100}").repeat(1), "\u{1100}");
+}
+
+#[test]
+fn escape() {
+    let t = TextStr::from_text("a\u{c5}\tb\n").unwrap();
+    assert_eq!(t.escape_debug().to_string(), "a\u{c5}\\tb\\n");
+    assert_eq!(t.escape_default().to_string(), "a\\u{c5}\\tb\\n");
+    assert_eq!(
+        t.escape_unicode().to_string(),
+        "\\u{61}\\u{c5}\\u{9}\\u{62}\\u{a}"
+    );
+
+    let s: &TextSubstr = t.as_ref();
+    assert_eq!(s.escape_debug().to_string(), t.escape_debug().to_string());
+    assert_eq!(
+        s.escape_default().to_string(),
+        t.escape_default().to_string()
+    );
+    assert_eq!(
+        s.escape_unicode().to_string(),
+        t.escape_unicode().to_string()
+    );
+}
+
+#[test]
+fn into_owned_types() {
+    let t = TextString::from_text("hello\n".to_owned()).unwrap();
+    assert_eq!(String::from(t.clone()), "hello\n");
+    assert_eq!(OsString::from(t.clone()), OsStr::new("hello\n"));
+    assert_eq!(PathBuf::from(t.clone()), Path::new("hello\n"));
+
+    let cow: Cow<'static, str> = t.clone().into();
+    assert!(matches!(cow, Cow::Owned(_)));
+    assert_eq!(cow, "hello\n");
+
+    let cow: Cow<'_, str> = t.as_text().into();
+    assert!(matches!(cow, Cow::Borrowed(_)));
+    assert_eq!(cow, "hello\n");
+}
+
+#[test]
+fn from_text_bytes_prefix() {
+    fn split(b: &[u8]) -> (&str, &[u8]) {
+        let (text, rest) = TextStr::from_text_bytes_prefix(b);
+        (text.as_str(), rest)
+    }
+
+    assert_eq!(split(b""), ("", &b""[..]));
+    assert_eq!(split(b"hello\n"), ("hello\n", &b""[..]));
+    assert_eq!(split(b"hello\xff\n"), ("hello", &b"\xff\n"[..]));
+    assert_eq!(split(b"hello\xe2\x80"), ("hello", &b"\xe2\x80"[..]));
+    assert_eq!(split(b"hello\r\n"), ("hello", &b"\r\n"[..]));
+
+    // Don't split a grapheme cluster, even where the prefix would be valid.
+    assert_eq!(split("xA\u{30a}".as_bytes()), ("x", "A\u{30a}".as_bytes()));
+    assert_eq!(
+        split("ab\u{200d}\u{7}".as_bytes()),
+        ("a", "b\u{200d}\u{7}".as_bytes())
+    );
+}
+
+#[test]
+fn validate_chunked() {
+    fn valid(b: &[u8]) -> Result<&str, TextError> {
+        TextStr::validate_chunked(b).finish().map(TextStr::as_str)
+    }
+
+    assert_eq!(valid(b"").unwrap(), "");
+    assert_eq!(valid(b"hello\n").unwrap(), "hello\n");
+    assert!(valid("\u{301}hello".as_bytes()).is_err());
+    assert!(valid("hello\u{200d}".as_bytes()).is_err());
+    assert_eq!(valid(b"hello\x07").unwrap_err().valid_up_to(), 5);
+    assert_eq!(valid(b"hello\xff").unwrap_err().valid_up_to(), 5);
+
+    // Large inputs are validated in several windows.
+    let big = "caf\u{e9} \u{4e00}\u{4e8c}\u{4e09}\n".repeat(1000);
+    let mut validator = TextStr::validate_chunked(big.as_bytes());
+    let mut windows = 0;
+    let mut last = 0;
+    for result in validator.by_ref() {
+        let n = result.unwrap();
+        assert!(n > last);
+        assert!(big.is_char_boundary(n));
+        last = n;
+        windows += 1;
+    }
+    assert!(windows > 1);
+    assert_eq!(last, big.len());
+    assert_eq!(validator.finish().unwrap(), big.as_str());
+
+    // Errors in later windows are reported at the same position as
+    // `from_text` reports them.
+    let bad = format!("{}A\u{30a}", big);
+    assert_eq!(
+        valid(bad.as_bytes()).unwrap_err(),
+        TextStr::from_text(&bad).unwrap_err()
+    );
+    let bad = format!("{}\u{7}", big);
+    assert_eq!(valid(bad.as_bytes()).unwrap_err().valid_up_to(), big.len());
+
+    // The quick check and the full check agree.
+    for s in [
+        "hello",
+        "\u{c5}",
+        "A\u{30a}",
+        "\u{1b}[m",
+        "a\u{1100}\u{1161}",
+    ] {
+        assert_eq!(TextStr::from_text(s).is_ok(), is_basic_text(s), "{:?}", s);
+    }
+}
+
+#[test]
+fn from_str() {
+    let t: TextString = "hello\n".parse().unwrap();
+    assert_eq!(t, "hello\n");
+    assert_eq!("".parse::<TextString>().unwrap(), "");
+    assert!("\u{301}".parse::<TextString>().is_err());
+    assert!("hello\r\n".parse::<TextString>().is_err());
+}
+
+#[test]
+fn from_char() {
+    assert_eq!(TextString::try_from('a').unwrap(), "a");
+    assert_eq!(TextString::try_from('\n').unwrap(), "\n");
+    assert_eq!(TextString::try_from('\u{c5}').unwrap(), "\u{c5}");
+    assert!(matches!(
+        TextString::try_from('\u{7}'),
+        Err(BasicTextError::ControlCode('\u{7}'))
+    ));
+    assert!(matches!(
+        TextString::try_from('\u{1b}'),
+        Err(BasicTextError::Escape)
+    ));
+    assert!(matches!(
+        TextString::try_from('\u{30a}'),
+        Err(BasicTextError::NonStarter('\u{30a}'))
+    ));
+    assert!(matches!(
+        TextString::try_from('\u{200d}'),
+        Err(BasicTextError::NonStarter('\u{200d}'))
+    ));
+    assert!(matches!(
+        TextString::try_from('\u{600}'),
+        Err(BasicTextError::NonEnding('\u{600}'))
+    ));
+
+    assert_eq!(TextString::from_char_lossy('a'), "a");
+    assert_eq!(TextString::from_char_lossy('\u{7}'), "\u{fffd}");
+    assert_eq!(TextString::from_char_lossy('\u{1b}'), "");
+    assert_eq!(TextString::from_char_lossy('\u{30a}'), "\u{34f}\u{30a}");
+    assert_eq!(TextString::from_char_lossy('\u{600}'), "\u{600}\u{34f}");
+    assert_eq!(TextString::from_char_lossy('\u{feff}'), "\u{2060}");
+}
+
+#[test]
+fn borrow_str() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::hash::Hasher;
+
+    let s = TextString::from_text("key".to_owned()).unwrap();
+    let mut text_hasher = DefaultHasher::new();
+    s.hash(&mut text_hasher);
+    let mut str_hasher = DefaultHasher::new();
+    "key".hash(&mut str_hasher);
+    assert_eq!(text_hasher.finish(), str_hasher.finish());
+
+    let mut map = HashMap::new();
+    map.insert(s, 1);
+    assert_eq!(map.get("key"), Some(&1));
+    assert_eq!(map.get("other"), None);
+}
+
+#[test]
+fn strip_prefix_suffix() {
+    let s = TextStr::from_text("x\u{301}yz\n").unwrap();
+    assert_eq!(s.strip_prefix("x").unwrap().as_str(), "\u{301}yz\n");
+    assert_eq!(s.strip_suffix("z\n").unwrap().as_str(), "x\u{301}y");
+    assert!(s.strip_prefix("y").is_none());
+    assert!(s.strip_suffix("y").is_none());
+}
+
+#[test]
+fn trim_matches() {
+    let s = TextStr::from_text("xxx\u{301}yxx").unwrap();
+    assert_eq!(s.trim_matches('x').as_str(), "\u{301}y");
+    assert_eq!(s.trim_start_matches("x").as_str(), "\u{301}yxx");
+    assert_eq!(s.trim_end_matches("x").as_str(), "xxx\u{301}y");
+}
+
+#[test]
+fn split() {
+    let s = TextStr::from_text("a,x\u{301},,b c\n").unwrap();
+    assert_eq!(
+        s.split(",").map(TextSubstr::as_str).collect::<Vec<_>>(),
+        ["a", "x\u{301}", "", "b c\n"]
+    );
+    assert_eq!(
+        s.splitn(2, ",").map(TextSubstr::as_str).collect::<Vec<_>>(),
+        ["a", "x\u{301},,b c\n"]
+    );
+    assert_eq!(
+        s.rsplit(",").map(TextSubstr::as_str).collect::<Vec<_>>(),
+        ["b c\n", "", "x\u{301}", "a"]
+    );
+    assert_eq!(
+        s.split_whitespace()
+            .map(TextSubstr::as_str)
+            .collect::<Vec<_>>(),
+        ["a,x\u{301},,b", "c"]
+    );
+}
+
 #[test]
 fn split_escape() {
     //assert_eq!(TextStr::from_text_bytes(b"\x1b[!p").unwrap_err().valid_up_to(),
@@ -1561,3 +3337,317 @@ fn split_escape() {
         0
     );
 }
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_short_input() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    for data in [&b""[..], b"\xff", b"hello", b"\xffhello\x1b"] {
+        let mut u = Unstructured::new(data);
+        let s = <&TextStr as Arbitrary>::arbitrary(&mut u).unwrap();
+        assert!(data.starts_with(s.as_bytes()));
+    }
+}
+
+#[test]
+fn into_lines() {
+    fn lines(s: &str) -> Vec<String> {
+        TextString::from_text(s.to_owned())
+            .unwrap()
+            .into_lines()
+            .into_iter()
+            .map(TextString::into_string)
+            .collect()
+    }
+
+    assert!(lines("").is_empty());
+    assert_eq!(lines("\n"), [""]);
+    assert_eq!(lines("a\nb\n"), ["a", "b"]);
+    assert_eq!(lines("a\nb"), ["a", "b"]);
+    assert_eq!(lines("a\n\nb\n"), ["a", "", "b"]);
+    assert_eq!(lines("a\n\u{301}b\n"), ["a", "\u{34f}\u{301}b"]);
+    assert_eq!(lines("a\u{200d}\nb\n"), ["a\u{200d}\u{34f}", "b"]);
+}
+
+#[test]
+fn eq_ignore_trailing_whitespace() {
+    fn eq(a: &str, b: &str) -> bool {
+        TextStr::from_text(a)
+            .unwrap()
+            .eq_ignore_trailing_whitespace(TextStr::from_text(b).unwrap())
+    }
+
+    assert!(eq("", ""));
+    assert!(eq("a\nb\n", "a\nb\n"));
+    assert!(eq("a  \nb\t\n", "a\nb\n"));
+    assert!(eq("a\nb\n", "a\nb"));
+    assert!(eq("a \u{3000}\n", "a\n"));
+    assert!(!eq(" a\n", "a\n"));
+    assert!(!eq("a b\n", "ab\n"));
+    assert!(!eq("a\n\n", "a\n"));
+    assert!(!eq("a\nb\n", "a\nc\n"));
+}
+
+#[test]
+fn quote_for_terminal() {
+    fn quote(s: &str) -> String {
+        TextStr::from_text(s)
+            .unwrap()
+            .quote_for_terminal()
+            .into_string()
+    }
+
+    assert_eq!(quote(""), "\"\"");
+    assert_eq!(quote("hello"), "\"hello\"");
+    assert_eq!(quote("a\tb\n"), "\"a\\tb\\n\"");
+    assert_eq!(quote("\\\""), "\"\\\\\\\"\"");
+    assert_eq!(quote("a\n\u{301}b"), "\"a\\n\u{34f}\u{301}b\"");
+    assert_eq!(quote("a\u{200d}\nb"), "\"a\u{200d}\\nb\"");
+}
+
+#[test]
+fn os_str() {
+    assert_eq!(from_os_str(OsStr::new("hello")).unwrap(), "hello");
+    assert_eq!(from_os_str(OsStr::new("A\u{30a}")).unwrap(), "\u{c5}");
+    from_os_str(OsStr::new("\u{7}")).unwrap_err();
+    from_os_str(OsStr::new("\u{301}")).unwrap_err();
+
+    assert_eq!(from_os_str_lossy(OsStr::new("hello")), "hello");
+    assert_eq!(from_os_str_lossy(OsStr::new("A\u{30a}")), "\u{c5}");
+    assert_eq!(from_os_str_lossy(OsStr::new("a\u{7}")), "a\u{fffd}");
+    assert_eq!(from_os_str_lossy(OsStr::new("\u{301}")), "\u{34f}\u{301}");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let invalid = OsStr::from_bytes(b"a\xffb");
+        assert_eq!(from_os_str(invalid).unwrap_err().into_bytes(), b"a\xffb");
+        assert_eq!(from_os_str_lossy(invalid), "a\u{fffd}b");
+    }
+}
+
+#[test]
+fn eq_ignore_case() {
+    fn eq(a: &str, b: &str) -> bool {
+        TextStr::from_text(a)
+            .unwrap()
+            .eq_ignore_case(TextStr::from_text(b).unwrap())
+    }
+
+    assert!(eq("", ""));
+    assert!(eq("Hello", "hELLO"));
+    assert!(eq("stra\u{df}e", "STRASSE"));
+    assert!(eq("\u{c5}ngstr\u{f6}m", "\u{e5}NGSTR\u{d6}M"));
+    assert!(eq("\u{3a3}\u{3c3}", "\u{3c2}\u{3a3}"));
+    assert!(!eq("hello", "hell"));
+    assert!(!eq("a", "\u{e5}"));
+
+    // Default caseless matching isn't tailored for Turkish.
+    assert!(eq("I", "i"));
+    assert!(!eq("I", "\u{131}"));
+    assert!(eq("\u{130}", "i\u{307}"));
+}
+
+#[test]
+fn from_text_vec_lossy() {
+    let v = b"hello\n".to_vec();
+    let ptr = v.as_ptr();
+    let s = TextString::from_text_vec_lossy(v);
+    assert_eq!(s, "hello\n");
+    assert_eq!(s.as_str().as_ptr(), ptr);
+
+    assert_eq!(TextString::from_text_vec_lossy(Vec::new()), "");
+    assert_eq!(TextString::from_text_vec_lossy(b"a\r\nb".to_vec()), "a\nb");
+    assert_eq!(
+        TextString::from_text_vec_lossy("A\u{30a}".as_bytes().to_vec()),
+        "\u{c5}"
+    );
+    assert_eq!(
+        TextString::from_text_vec_lossy(b"a\xffb".to_vec()),
+        "a\u{fffd}b"
+    );
+    assert_eq!(
+        TextString::from_text_vec_lossy("\u{feff}x".as_bytes().to_vec()),
+        "\u{2060}x"
+    );
+}
+
+#[test]
+fn reserve_and_shrink() {
+    let mut s = TextString::new();
+    s.try_reserve(64).unwrap();
+    assert!(s.capacity() >= 64);
+    s.try_reserve_exact(128).unwrap();
+    assert!(s.capacity() >= 128);
+    s.push_text(TextStr::from_text("hello\n").unwrap());
+    s.shrink_to(16);
+    assert!(s.capacity() >= 16 && s.capacity() < 128);
+    assert_eq!(s, "hello\n");
+}
+
+#[test]
+fn default_ignorables() {
+    fn strip(s: &str) -> TextString {
+        TextStr::from_text(s).unwrap().strip_default_ignorables()
+    }
+
+    assert!(!TextStr::from_text("hello")
+        .unwrap()
+        .contains_default_ignorable());
+    assert!(TextStr::from_text("a\u{200b}b")
+        .unwrap()
+        .contains_default_ignorable());
+    assert!(TextStr::from_text("👨\u{200d}💻")
+        .unwrap()
+        .contains_default_ignorable());
+
+    assert_eq!(strip(""), "");
+    assert_eq!(strip("hello\n"), "hello\n");
+    assert_eq!(strip("a\u{ad}b\u{200b}c\u{2060}d"), "abcd");
+    assert_eq!(strip("a\u{200d}b"), "ab");
+
+    // Emoji ZWJ sequences are kept, but their variation selectors aren't.
+    assert_eq!(strip("👨\u{200d}💻"), "👨\u{200d}💻");
+    assert_eq!(strip("👨\u{1f3fd}\u{200d}💻"), "👨\u{1f3fd}\u{200d}💻");
+    assert_eq!(strip("❤\u{fe0f}\u{200d}🔥"), "❤\u{200d}🔥");
+    assert_eq!(strip("❤\u{fe0f}"), "❤");
+    assert_eq!(strip("👨\u{200d}x"), "👨x");
+
+    // Removing a CGJ may require renormalizing, or reinserting it.
+    assert_eq!(strip("e\u{34f}\u{301}"), "\u{e9}");
+    assert_eq!(strip("\u{34f}\u{301}x"), "\u{34f}\u{301}x");
+    assert_eq!(strip("\u{600}\u{34f}"), "\u{600}\u{34f}");
+}
+
+#[test]
+fn lines_with_endings() {
+    fn lines(s: &str) -> Vec<&str> {
+        TextStr::from_text(s)
+            .unwrap()
+            .lines_with_endings()
+            .map(TextSubstr::as_str)
+            .collect()
+    }
+
+    assert_eq!(lines(""), Vec::<&str>::new());
+    assert_eq!(lines("\n"), ["\n"]);
+    assert_eq!(lines("a\nb\n"), ["a\n", "b\n"]);
+    assert_eq!(lines("a\n\nb"), ["a\n", "\n", "b"]);
+    assert_eq!(lines("x\u{301}\n\u{301}y"), ["x\u{301}\n", "\u{301}y"]);
+}
+
+#[test]
+fn split_once() {
+    let s = TextStr::from_text("key=value=x\u{301}").unwrap();
+    let (k, v) = s.split_once("=").unwrap();
+    assert_eq!((k.as_str(), v.as_str()), ("key", "value=x\u{301}"));
+    let (k, v) = s.rsplit_once("=").unwrap();
+    assert_eq!((k.as_str(), v.as_str()), ("key=value", "x\u{301}"));
+    assert!(s.split_once(":").is_none());
+    assert!(s.rsplit_once(":").is_none());
+
+    // The halves may begin with a non-starter.
+    let s = TextStr::from_text("x\u{301}\u{302}").unwrap();
+    let (a, b) = s.split_once("\u{301}").unwrap();
+    assert_eq!((a.as_str(), b.as_str()), ("x", "\u{302}"));
+}
+
+#[test]
+fn push_cow() {
+    let owned = TextString::from_text("hello".to_owned()).unwrap();
+    let ptr = owned.as_str().as_ptr();
+    let mut s = TextString::new();
+    s.push_cow(Cow::Owned(owned));
+    assert_eq!(s.as_str().as_ptr(), ptr);
+    s.push_cow(Cow::Borrowed(TextStr::from_text(" world").unwrap()));
+    s.push_cow(Cow::Owned(TextString::from_text("!\n".to_owned()).unwrap()));
+    assert_eq!(s, "hello world!\n");
+
+    // Appending a CGJ-guarded string after a CGJ doesn't double the CGJ.
+    let mut s = TextString::from_text("a\u{34f}".to_owned()).unwrap();
+    s.push_cow(Cow::Owned(
+        TextString::from_text("\u{34f}\u{301}".to_owned()).unwrap(),
+    ));
+    assert_eq!(s, "a\u{34f}\u{301}");
+}
+
+#[test]
+fn try_push_str() {
+    let mut s = TextString::new();
+    s.try_push_str("hello").unwrap();
+    s.try_push_str(" world\n").unwrap();
+    assert_eq!(s, "hello world\n");
+
+    assert_eq!(s.try_push_str("ok\x07").unwrap_err().valid_up_to(), 2);
+    s.try_push_str("\u{301}").unwrap_err();
+    assert_eq!(s, "hello world\n");
+
+    // A Hangul syllable split across the seam isn't in NFC.
+    let mut s = TextString::from_text("\u{1100}".to_owned()).unwrap();
+    assert_eq!(s.try_push_str("\u{1161}").unwrap_err().valid_up_to(), 0);
+    assert_eq!(s, "\u{1100}");
+}
+
+#[test]
+fn push_str_lossy() {
+    let mut s = TextString::new();
+    s.push_str_lossy("hello\x07");
+    s.push_str_lossy("\u{301}");
+    assert_eq!(s, "hello\u{fffd}\u{34f}\u{301}");
+
+    let mut s = TextString::from_text("a\u{1100}".to_owned()).unwrap();
+    s.push_str_lossy("\u{1161}\u{11a8}b");
+    assert_eq!(s, "a\u{ac01}b");
+}
+
+#[test]
+fn empty() {
+    const EMPTY: &TextStr = TextStr::empty();
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY, <&TextStr>::default());
+}
+
+#[test]
+fn bytes_eq() {
+    let s = TextStr::from_text("caf\u{e9}").unwrap();
+    assert!(s.bytes_eq(b"caf\xc3\xa9"));
+    assert!(!s.bytes_eq(b"caf"));
+    assert!(!s.bytes_eq(b"caf\xc3\xa9\xff"));
+    assert!(s.starts_with_bytes(b""));
+    assert!(s.starts_with_bytes(b"caf\xc3"));
+    assert!(!s.starts_with_bytes(b"cafe"));
+}
+
+#[test]
+fn debug_escapes() {
+    // `Debug` escapes combining marks and invisible scalar values, so that
+    // they're visible and don't combine with surrounding output.
+    let s = TextStr::from_text("x\u{301}\u{200b}\u{2060}\t").unwrap();
+    assert_eq!(
+        format!("{:?}", s),
+        r#"TextStr("x\u{301}\u{200b}\u{2060}\t")"#
+    );
+}
+
+#[test]
+fn set_from_text() {
+    let mut s = TextString::with_capacity(64);
+    let ptr = s.as_str().as_ptr();
+
+    s.set_from_text("hello").unwrap();
+    assert_eq!(s, "hello");
+    s.set_from_text("caf\u{e9}\n").unwrap();
+    assert_eq!(s, "caf\u{e9}\n");
+    assert_eq!(s.as_str().as_ptr(), ptr);
+
+    assert_eq!(s.set_from_text("ok\x07").unwrap_err().valid_up_to(), 2);
+    assert_eq!(s, "caf\u{e9}\n");
+
+    s.clear();
+    assert!(s.is_empty());
+    assert!(s.capacity() >= 64);
+    s.set_from_text("again").unwrap();
+    assert_eq!(s.as_str().as_ptr(), ptr);
+}
@@ -1,12 +1,15 @@
 //! The `TextSubstring` and `TextSubstr` types.
 
-use crate::{FromTextError, TextError, TextReader, TextWriter};
-use basic_text_internals::is_basic_text_substr;
-use basic_text_internals::unicode::{BOM, WJ};
+use crate::{FromTextError, TextError, TextReader, TextStr, TextWriter};
+use basic_text_internals::unicode::{BOM, CGJ, DOTTED_CIRCLE, WJ};
+use basic_text_internals::unicode_normalization::char::canonical_combining_class;
+use basic_text_internals::unicode_normalization::{is_nfc_stream_safe_quick, IsNormalized};
+use basic_text_internals::{
+    is_basic_text_end, is_basic_text_start, is_basic_text_substr, StreamSafeNfc,
+};
 use layered_io::Bufferable;
 use std::borrow::{Borrow, BorrowMut, Cow};
 use std::cmp::Ordering;
-#[cfg(try_reserve)]
 use std::collections::TryReserveError;
 use std::ffi::OsStr;
 use std::fmt::{self, Debug, Display, Formatter};
@@ -16,12 +19,13 @@ use std::net::{SocketAddr, ToSocketAddrs};
 use std::ops::{Deref, DerefMut, Index, Range, RangeFrom, RangeTo};
 use std::path::Path;
 #[cfg(pattern)]
-use std::str::pattern::{Pattern, ReverseSearcher};
+use std::str::pattern::{DoubleEndedSearcher, Pattern, ReverseSearcher};
 use std::str::{
     self, Bytes, CharIndices, Chars, EncodeUtf16, EscapeDebug, EscapeDefault, EscapeUnicode,
     FromStr, Lines, MatchIndices, Matches, RMatchIndices, RMatches,
 };
 use std::vec;
+use unicode_segmentation::GraphemeCursor;
 use utf8_io::WriteStr;
 
 /// A substring of a Basic Text string or stream.
@@ -187,10 +191,16 @@ impl TextSubstring {
     /// This function is unsafe because it does not check that the bytes passed
     /// to it are valid Basic Text. If this constraint is violated, undefined
     /// behavior results, as the rest of this crate assumes that `&TextSubstr`s
-    /// are valid Basic Text.
+    /// are valid Basic Text. In debug builds, this is checked with a
+    /// `debug_assert!`.
     #[inline]
     #[must_use]
     pub unsafe fn from_text_vec_unchecked(vec: Vec<u8>) -> Self {
+        debug_assert!(
+            str::from_utf8(&vec).is_ok_and(is_basic_text_substr),
+            "invalid Basic Text: {:?}",
+            vec
+        );
         Self::from_text_unchecked(String::from_utf8_unchecked(vec))
     }
 
@@ -245,6 +255,30 @@ impl TextSubstring {
         self
     }
 
+    /// Appends a given substring slice onto the end of this `TextSubstring`.
+    ///
+    /// Unlike [`TextString::push_text`], the appended substring may begin
+    /// with a non-starter, which may compose with the end of `self`, so the
+    /// seam is renormalized.
+    ///
+    /// [`TextString::push_text`]: https://docs.rs/basic-text/latest/basic_text/struct.TextString.html#method.push_text
+    pub fn push_text_substr(&mut self, s: &TextSubstr) {
+        // Only scalar values after the last starter can interact with `s`.
+        let seam = self
+            .0
+            .char_indices()
+            .rev()
+            .find(|(_, c)| canonical_combining_class(*c) == 0)
+            .map_or(0, |(i, _)| i);
+
+        let mut tail = self.0.split_off(seam);
+        tail.push_str(&s.0);
+        if is_nfc_stream_safe_quick(tail.chars()) != IsNormalized::Yes {
+            tail = StreamSafeNfc::new(tail.chars()).collect();
+        }
+        self.0.push_str(&tail);
+    }
+
     /// Returns this `TextSubstring`'s capacity, in bytes.
     #[inline]
     #[must_use]
@@ -268,7 +302,6 @@ impl TextSubstring {
 
     /// Tries to reserve capacity for at least `additional` more elements to
     /// be inserted in the given `TextSubstring`.
-    #[cfg(try_reserve)]
     #[inline]
     pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.0.try_reserve(additional)
@@ -276,7 +309,6 @@ impl TextSubstring {
 
     /// Tries to reserves the minimum capacity for exactly `additional` more
     /// elements to be inserted in the given `TextSubstring`.
-    #[cfg(try_reserve)]
     #[inline]
     pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.0.try_reserve_exact(additional)
@@ -288,8 +320,7 @@ impl TextSubstring {
         self.0.shrink_to_fit();
     }
 
-    /// Shrinks the capacity of this `String` with a lower bound.
-    #[cfg(shrink_to)]
+    /// Shrinks the capacity of this `TextSubstring` with a lower bound.
     #[inline]
     pub fn shrink_to(&mut self, min_capacity: usize) {
         self.0.shrink_to(min_capacity);
@@ -476,6 +507,15 @@ impl BorrowMut<TextSubstr> for TextSubstring {
     }
 }
 
+// `TextSubstring`'s `Eq`, `PartialOrd`, and `Hash` impls defer to the inner
+// `String`, so they're consistent with those of `str`.
+impl Borrow<str> for TextSubstring {
+    #[inline]
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
 impl AsMut<TextSubstr> for TextSubstring {
     #[inline]
     fn as_mut(&mut self) -> &mut TextSubstr {
@@ -532,6 +572,16 @@ impl TextSubstr {
         Self::from_text_unchecked(str::from_utf8_unchecked(b))
     }
 
+    /// Returns an empty text string slice.
+    ///
+    /// Unlike `<&TextSubstr>::default()`, this can be used in `const` contexts.
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> &'static Self {
+        let ptr: *const str = "";
+        unsafe { &*(ptr as *const Self) }
+    }
+
     /// Converts a string slice to a text string slice without checking that
     /// the string contains valid Basic Text.
     ///
@@ -540,9 +590,11 @@ impl TextSubstr {
     /// This function is unsafe because it does not check that the bytes passed
     /// to it are valid Basic Text. If this constraint is violated, undefined
     /// behavior results, as the rest of this crate assumes that `&TextSubstr`s
-    /// are valid Basic Text.
+    /// are valid Basic Text. In debug builds, this is checked with a
+    /// `debug_assert!`.
     #[inline]
     pub unsafe fn from_text_unchecked(s: &str) -> &Self {
+        debug_assert!(is_basic_text_substr(s), "invalid Basic Text: {:?}", s);
         let ptr: *const str = s;
         &*(ptr as *const Self)
     }
@@ -569,9 +621,11 @@ impl TextSubstr {
     /// This function is unsafe because it does not check that the string
     /// passed to it is valid Basic Text. If this constraint is violated,
     /// undefined behavior results, as the rest of this crate assumes that
-    /// `&TextSubstr`s are valid Basic Text.
+    /// `&TextSubstr`s are valid Basic Text. In debug builds, this is checked
+    /// with a `debug_assert!`.
     #[inline]
     pub unsafe fn from_text_unchecked_mut(s: &mut str) -> &mut Self {
+        debug_assert!(is_basic_text_substr(s), "invalid Basic Text: {:?}", s);
         let ptr: *mut str = s;
         &mut *(ptr as *mut Self)
     }
@@ -618,7 +672,25 @@ impl TextSubstr {
         self.0.is_empty()
     }
 
-    // TODO: is_char_boundary?
+    /// Checks that `index`-th byte is the first byte in a UTF-8 code point
+    /// sequence or the end of the string.
+    #[inline]
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        self.0.is_char_boundary(index)
+    }
+
+    /// Checks that `index`-th byte is the first byte of an extended grapheme
+    /// cluster or the end of the string.
+    ///
+    /// This is stronger than `is_char_boundary`; splitting a text string
+    /// slice within a grapheme cluster may not produce valid Basic Text.
+    #[inline]
+    pub fn is_grapheme_boundary(&self, index: usize) -> bool {
+        self.is_char_boundary(index)
+            && GraphemeCursor::new(index, self.0.len(), true)
+                .is_boundary(&self.0, 0)
+                .unwrap()
+    }
 
     /// Converts a text string slice to a byte slice.
     #[inline]
@@ -626,6 +698,22 @@ impl TextSubstr {
         self.0.as_bytes()
     }
 
+    /// Tests whether the bytes of this text string slice are equal to
+    /// `bytes`, without validating `bytes`.
+    #[inline]
+    pub fn bytes_eq(&self, bytes: &[u8]) -> bool {
+        self.0.as_bytes() == bytes
+    }
+
+    /// Tests whether the bytes of this text string slice start with `bytes`,
+    /// without validating `bytes`.
+    ///
+    /// `bytes` needn't end on a scalar value boundary.
+    #[inline]
+    pub fn starts_with_bytes(&self, bytes: &[u8]) -> bool {
+        self.0.as_bytes().starts_with(bytes)
+    }
+
     /// Converts a mutable text string slice to a mutable byte slice.
     ///
     /// # Safety
@@ -659,6 +747,39 @@ impl TextSubstr {
         &self.0
     }
 
+    /// Returns `true` if `self` is empty or begins with a scalar value which
+    /// is valid at the beginning of a Basic Text string.
+    #[inline]
+    pub fn is_valid_text_start(&self) -> bool {
+        match self.0.chars().next() {
+            Some(c) => is_basic_text_start(c),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if `self` is empty or ends with a scalar value which
+    /// is valid at the end of a Basic Text string.
+    #[inline]
+    pub fn is_valid_text_end(&self) -> bool {
+        match self.0.chars().next_back() {
+            Some(c) => is_basic_text_end(c),
+            None => true,
+        }
+    }
+
+    /// Returns `self` as a `&TextStr` if it is valid at both the beginning
+    /// and end of a Basic Text string, or `None` otherwise.
+    #[inline]
+    pub fn try_into_text(&self) -> Option<&TextStr> {
+        if self.is_valid_text_start() && self.is_valid_text_end() {
+            // Safety: The contents are a valid Basic Text substring, and we
+            // just checked the boundary conditions.
+            Some(unsafe { TextStr::from_text_unchecked(&self.0) })
+        } else {
+            None
+        }
+    }
+
     /// Divide one text string slice into two at an index.
     #[inline]
     pub fn split_at(&self, mid: usize) -> (&Self, &Self) {
@@ -702,7 +823,29 @@ impl TextSubstr {
         self.0.bytes()
     }
 
-    // TODO: split*?
+    /// An iterator over the pieces of a text string slice separated by
+    /// whitespace, as text substring slices.
+    #[inline]
+    pub fn split_whitespace(&self) -> impl Iterator<Item = &TextSubstr> {
+        self.0
+            .split_whitespace()
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over the lines of a text string, as text substring
+    /// slices, including their trailing '\n's.
+    ///
+    /// Unlike [`lines`], the final line is yielded without a '\n' if the
+    /// string doesn't end with one, so concatenating the lines reproduces the
+    /// string exactly. An empty string yields no lines.
+    ///
+    /// [`lines`]: Self::lines
+    #[inline]
+    pub fn lines_with_endings(&self) -> impl Iterator<Item = &TextSubstr> {
+        self.0
+            .split_inclusive('\n')
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
 
     /// An iterator over the lines of a text string, as text string slices.
     ///
@@ -835,7 +978,144 @@ impl TextSubstr {
         self.0.rfind(pat)
     }
 
-    // TODO: *split*?
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn split<'a, P>(&'a self, pat: P) -> impl Iterator<Item = &'a TextSubstr>
+    where
+        P: Pattern<'a>,
+    {
+        self.0
+            .split(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn split<'a>(&'a self, pat: &'a str) -> impl Iterator<Item = &'a TextSubstr> {
+        self.0
+            .split(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over at most `n` pieces of this text string slice
+    /// separated by matches of a pattern, as text substring slices. The last
+    /// piece contains the remainder of the slice.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn splitn<'a, P>(&'a self, n: usize, pat: P) -> impl Iterator<Item = &'a TextSubstr>
+    where
+        P: Pattern<'a>,
+    {
+        self.0
+            .splitn(n, pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over at most `n` pieces of this text string slice
+    /// separated by matches of a pattern, as text substring slices. The last
+    /// piece contains the remainder of the slice.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn splitn<'a>(&'a self, n: usize, pat: &'a str) -> impl Iterator<Item = &'a TextSubstr> {
+        self.0
+            .splitn(n, pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices, yielded in reverse
+    /// order.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn rsplit<'a, P>(&'a self, pat: P) -> impl Iterator<Item = &'a TextSubstr>
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        self.0
+            .rsplit(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// An iterator over the pieces of this text string slice separated by
+    /// matches of a pattern, as text substring slices, yielded in reverse
+    /// order.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn rsplit<'a>(&'a self, pat: &'a str) -> impl Iterator<Item = &'a TextSubstr> {
+        self.0
+            .rsplit(pat)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Splits this text substring slice on the first occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn split_once<'a, P>(&'a self, pat: P) -> Option<(&'a TextSubstr, &'a TextSubstr)>
+    where
+        P: Pattern<'a>,
+    {
+        self.0.split_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    /// Splits this text substring slice on the first occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn split_once<'a>(&'a self, pat: &str) -> Option<(&'a TextSubstr, &'a TextSubstr)> {
+        self.0.split_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    /// Splits this text substring slice on the last occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn rsplit_once<'a, P>(&'a self, pat: P) -> Option<(&'a TextSubstr, &'a TextSubstr)>
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        self.0.rsplit_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    /// Splits this text substring slice on the last occurrence of a pattern,
+    /// returning the text substring slices before and after it, or `None`
+    /// if the pattern doesn't match.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn rsplit_once<'a>(&'a self, pat: &str) -> Option<(&'a TextSubstr, &'a TextSubstr)> {
+        self.0.rsplit_once(pat).map(|(a, b)| unsafe {
+            (
+                TextSubstr::from_text_unchecked(a),
+                TextSubstr::from_text_unchecked(b),
+            )
+        })
+    }
+
+    // TODO: rsplitn, split_terminator, rsplit_terminator?
 
     /// An iterator over the disjoint matches of a pattern within the given
     /// text string slice.
@@ -943,8 +1223,119 @@ impl TextSubstr {
         unsafe { Self::from_text_unchecked(self.0.trim_end()) }
     }
 
-    // TODO: trim_matches, trim_start_matches, strip_prefix, strip_suffix,
-    // trim_end_matches?
+    /// Returns a text string slice with all prefixes and suffixes that match
+    /// a pattern repeatedly removed.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn trim_matches<'a, P>(&'a self, pat: P) -> &'a TextSubstr
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: DoubleEndedSearcher<'a>,
+    {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all prefixes and suffixes that match
+    /// a pattern repeatedly removed.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn trim_matches(&self, pat: char) -> &TextSubstr {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all prefixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn trim_start_matches<'a, P>(&'a self, pat: P) -> &'a TextSubstr
+    where
+        P: Pattern<'a>,
+    {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_start_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all prefixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn trim_start_matches<'a>(&'a self, pat: &str) -> &'a TextSubstr {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_start_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all suffixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn trim_end_matches<'a, P>(&'a self, pat: P) -> &'a TextSubstr
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_end_matches(pat)) }
+    }
+
+    /// Returns a text string slice with all suffixes that match a pattern
+    /// repeatedly removed.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn trim_end_matches<'a>(&'a self, pat: &str) -> &'a TextSubstr {
+        unsafe { TextSubstr::from_text_unchecked(self.0.trim_end_matches(pat)) }
+    }
+
+    /// Returns a text string slice with the prefix removed.
+    ///
+    /// If the string starts with the pattern `prefix`, returns the substring
+    /// after the prefix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn strip_prefix<'a, P>(&'a self, prefix: P) -> Option<&'a TextSubstr>
+    where
+        P: Pattern<'a>,
+    {
+        self.0
+            .strip_prefix(prefix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Returns a text string slice with the prefix removed.
+    ///
+    /// If the string starts with the pattern `prefix`, returns the substring
+    /// after the prefix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn strip_prefix<'a>(&'a self, prefix: &str) -> Option<&'a TextSubstr> {
+        self.0
+            .strip_prefix(prefix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Returns a text string slice with the suffix removed.
+    ///
+    /// If the string ends with the pattern `suffix`, returns the substring
+    /// before the suffix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(pattern)]
+    #[inline]
+    pub fn strip_suffix<'a, P>(&'a self, suffix: P) -> Option<&'a TextSubstr>
+    where
+        P: Pattern<'a>,
+        <P as Pattern<'a>>::Searcher: ReverseSearcher<'a>,
+    {
+        self.0
+            .strip_suffix(suffix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
+
+    /// Returns a text string slice with the suffix removed.
+    ///
+    /// If the string ends with the pattern `suffix`, returns the substring
+    /// before the suffix, wrapped in `Some`. Otherwise, returns `None`.
+    #[cfg(not(pattern))]
+    #[inline]
+    pub fn strip_suffix<'a>(&'a self, suffix: &str) -> Option<&'a TextSubstr> {
+        self.0
+            .strip_suffix(suffix)
+            .map(|s| unsafe { TextSubstr::from_text_unchecked(s) })
+    }
 
     /// Parses this text string slice into another type.
     #[inline]
@@ -1025,6 +1416,26 @@ impl TextSubstr {
         self.0.escape_unicode()
     }
 
+    /// Return an object implementing [`Display`] which prints `self` with
+    /// a U+25CC (DOTTED CIRCLE) before a leading non-starter and a U+34F
+    /// (CGJ) after a trailing non-ending scalar value, so that the output
+    /// doesn't combine with whatever is printed around it.
+    ///
+    /// This is intended for logging and debugging; `self` is not modified.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use basic_text::TextSubstr;
+    ///
+    /// let s = TextSubstr::from_text("\u{301}x\u{200d}").unwrap();
+    /// assert_eq!(s.display_lossy().to_string(), "\u{25cc}\u{301}x\u{200d}\u{34f}");
+    /// ```
+    #[inline]
+    pub fn display_lossy(&self) -> impl Display + '_ {
+        DisplayLossy(self)
+    }
+
     // TODO: make_ascii_uppercase, make_ascii_lowercase, replace*,
     // to_lowercase, to_uppercase, to_ascii_uppercase, to_ascii_lowercase;
     // determine whether these can be done without breaking NFC.
@@ -1085,6 +1496,22 @@ impl Display for TextSubstr {
     }
 }
 
+/// The value returned by [`TextSubstr::display_lossy`].
+struct DisplayLossy<'a>(&'a TextSubstr);
+
+impl Display for DisplayLossy<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.0.is_valid_text_start() {
+            Display::fmt(&DOTTED_CIRCLE, f)?;
+        }
+        Display::fmt(self.0.as_str(), f)?;
+        if !self.0.is_valid_text_end() {
+            Display::fmt(&CGJ, f)?;
+        }
+        Ok(())
+    }
+}
+
 impl Ord for TextSubstr {
     #[inline]
     fn cmp(&self, other: &Self) -> Ordering {
@@ -1213,14 +1640,15 @@ impl From<&TextSubstr> for Box<TextSubstr> {
 impl<'a> arbitrary::Arbitrary<'a> for &'a TextSubstr {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
         let size = u.arbitrary_len::<u8>()?;
-        match TextSubstr::from_text_bytes(&u.peek_bytes(size).unwrap()) {
+        let bytes = u.peek_bytes(size).ok_or(arbitrary::Error::NotEnoughData)?;
+        match TextSubstr::from_text_bytes(bytes) {
             Ok(s) => {
-                u.bytes(size).unwrap();
+                u.bytes(size)?;
                 Ok(s)
             }
             Err(e) => {
                 let i = e.valid_up_to();
-                let valid = u.bytes(i).unwrap();
+                let valid = u.bytes(i)?;
                 let s = unsafe {
                     debug_assert!(TextSubstr::from_text_bytes(valid).is_ok());
                     TextSubstr::from_text_bytes_unchecked(valid)
@@ -1330,6 +1758,66 @@ fn normalize_string() {
     assert_eq!(composed_nl, &TextSubstring::from_text_lossy(unnormal_nl));
 }
 
+#[test]
+fn strip_prefix_suffix() {
+    let s = TextSubstr::from_text("\u{301}yx\u{301}").unwrap();
+    assert_eq!(s.strip_prefix("\u{301}").unwrap().as_str(), "yx\u{301}");
+    assert_eq!(s.strip_suffix("\u{301}").unwrap().as_str(), "\u{301}yx");
+    assert!(s.strip_prefix("y").is_none());
+    assert!(s.strip_suffix("y").is_none());
+}
+
+#[test]
+fn try_into_text() {
+    let s = TextSubstr::from_text("").unwrap();
+    assert!(s.is_valid_text_start() && s.is_valid_text_end());
+    assert_eq!(s.try_into_text().unwrap().as_str(), "");
+
+    let s = TextSubstr::from_text("hello\n").unwrap();
+    assert_eq!(s.try_into_text().unwrap().as_str(), "hello\n");
+
+    let s = TextSubstr::from_text("\u{301}x").unwrap();
+    assert!(!s.is_valid_text_start());
+    assert!(s.is_valid_text_end());
+    assert!(s.try_into_text().is_none());
+
+    let s = TextSubstr::from_text("x\u{200d}").unwrap();
+    assert!(s.is_valid_text_start());
+    assert!(!s.is_valid_text_end());
+    assert!(s.try_into_text().is_none());
+}
+
+#[test]
+fn trim_matches() {
+    let s = TextSubstr::from_text("\u{301}\u{301}yx\u{301}").unwrap();
+    assert_eq!(s.trim_matches('\u{301}').as_str(), "yx");
+    assert_eq!(s.trim_start_matches("\u{301}").as_str(), "yx\u{301}");
+    assert_eq!(s.trim_end_matches("\u{301}").as_str(), "\u{301}\u{301}yx");
+}
+
+#[test]
+fn split() {
+    let s = TextSubstr::from_text("\u{301} x\u{301}").unwrap();
+    assert_eq!(
+        s.split(" ").map(TextSubstr::as_str).collect::<Vec<_>>(),
+        ["\u{301}", "x\u{301}"]
+    );
+    assert_eq!(
+        s.splitn(1, " ").map(TextSubstr::as_str).collect::<Vec<_>>(),
+        ["\u{301} x\u{301}"]
+    );
+    assert_eq!(
+        s.rsplit(" ").map(TextSubstr::as_str).collect::<Vec<_>>(),
+        ["x\u{301}", "\u{301}"]
+    );
+    assert_eq!(
+        s.split_whitespace()
+            .map(TextSubstr::as_str)
+            .collect::<Vec<_>>(),
+        ["\u{301}", "x\u{301}"]
+    );
+}
+
 #[test]
 fn validate_string() {
     assert!(TextSubstr::from_text_bytes(b"").is_ok());
@@ -1370,3 +1858,114 @@ fn split_escape() {
         0
     );
 }
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_short_input() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    for data in [&b""[..], b"\xff", b"hello", b"\xffhello\x1b"] {
+        let mut u = Unstructured::new(data);
+        let s = <&TextSubstr as Arbitrary>::arbitrary(&mut u).unwrap();
+        assert!(data.starts_with(s.as_bytes()));
+    }
+}
+
+#[test]
+fn boundaries() {
+    let s = TextSubstr::from_text("\u{301}x\u{301}").unwrap();
+    assert!(s.is_char_boundary(0));
+    assert!(!s.is_char_boundary(1));
+    assert!(s.is_char_boundary(2));
+    assert!(s.is_grapheme_boundary(0));
+    assert!(s.is_grapheme_boundary(2));
+    assert!(!s.is_grapheme_boundary(3));
+    assert!(s.is_grapheme_boundary(5));
+    assert!(!s.is_grapheme_boundary(6));
+}
+
+#[test]
+fn display_lossy() {
+    let s = TextSubstr::from_text("").unwrap();
+    assert_eq!(s.display_lossy().to_string(), "");
+
+    let s = TextSubstr::from_text("hello").unwrap();
+    assert_eq!(s.display_lossy().to_string(), "hello");
+
+    let s = TextSubstr::from_text("\u{301}x").unwrap();
+    assert_eq!(s.display_lossy().to_string(), "\u{25cc}\u{301}x");
+    assert_eq!(s.as_str(), "\u{301}x");
+
+    let s = TextSubstr::from_text("x\u{200d}").unwrap();
+    assert_eq!(s.display_lossy().to_string(), "x\u{200d}\u{34f}");
+}
+
+#[test]
+fn push_text_substr() {
+    let mut s = TextSubstring::new();
+    s.push_text_substr(TextSubstr::from_text("e").unwrap());
+    s.push_text_substr(TextSubstr::from_text("\u{301}").unwrap());
+    assert_eq!(s.as_str(), "\u{e9}");
+
+    s.push_text_substr(TextSubstr::from_text("x\u{301}").unwrap());
+    assert_eq!(s.as_str(), "\u{e9}x\u{301}");
+
+    let mut s = TextSubstring::from(TextSubstr::from_text("\u{1100}").unwrap());
+    s.push_text_substr(TextSubstr::from_text("\u{1161}").unwrap());
+    assert_eq!(s.as_str(), "\u{ac00}");
+
+    let mut s = TextSubstring::from(TextSubstr::from_text("\u{301}").unwrap());
+    s.push_text_substr(TextSubstr::from_text("\u{301}").unwrap());
+    assert_eq!(s.as_str(), "\u{301}\u{301}");
+
+    // Combining marks are reordered across the seam.
+    let mut s = TextSubstring::from(TextSubstr::from_text("x\u{301}").unwrap());
+    s.push_text_substr(TextSubstr::from_text("\u{323}").unwrap());
+    assert_eq!(s.as_str(), "x\u{323}\u{301}");
+}
+
+#[test]
+fn lines_with_endings() {
+    let s = TextSubstr::from_text("\u{301}a\nb\nc\u{200d}").unwrap();
+    assert_eq!(
+        s.lines_with_endings()
+            .map(TextSubstr::as_str)
+            .collect::<Vec<_>>(),
+        ["\u{301}a\n", "b\n", "c\u{200d}"]
+    );
+    assert_eq!(
+        s.lines_with_endings()
+            .map(TextSubstr::as_str)
+            .collect::<String>(),
+        s.as_str()
+    );
+}
+
+#[test]
+fn empty() {
+    const EMPTY: &TextSubstr = TextSubstr::empty();
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY, <&TextSubstr>::default());
+}
+
+#[test]
+fn bytes_eq() {
+    let s = TextSubstr::from_text("caf\u{e9}").unwrap();
+    assert!(s.bytes_eq(b"caf\xc3\xa9"));
+    assert!(!s.bytes_eq(b"caf"));
+    assert!(!s.bytes_eq(b"caf\xc3\xa9\xff"));
+    assert!(s.starts_with_bytes(b""));
+    assert!(s.starts_with_bytes(b"caf\xc3"));
+    assert!(!s.starts_with_bytes(b"cafe"));
+}
+
+#[test]
+fn debug_escapes() {
+    // `Debug` escapes combining marks and invisible scalar values, so that
+    // they're visible and don't combine with surrounding output.
+    let s = TextSubstr::from_text("x\u{301}\u{200b}\u{2060}\t").unwrap();
+    assert_eq!(
+        format!("{:?}", s),
+        r#"TextSubstr("x\u{301}\u{200b}\u{2060}\t")"#
+    );
+}
@@ -1,5 +1,5 @@
 use crate::text_output::TextOutput;
-use crate::{TextSubstr, WriteText};
+use crate::{TextStr, TextSubstr, WriteStats, WriteText};
 #[cfg(windows)]
 use io_extras::os::windows::{
     AsHandleOrSocket, AsRawHandleOrSocket, BorrowedHandleOrSocket, RawHandleOrSocket,
@@ -24,6 +24,10 @@ use {
 /// `write` is not guaranteed to perform a single operation, because short
 /// writes could produce invalid UTF-8, so `write` will retry as needed.
 ///
+/// If `write` is given data which is invalid after a valid prefix, it writes
+/// the prefix and returns its length, and reports the error on the next call
+/// to `write`.
+///
 /// # Examples
 ///
 /// ```rust
@@ -36,7 +40,7 @@ use {
 ///
 /// output.close().unwrap();
 /// ```
-pub struct TextWriter<Inner> {
+pub struct TextWriter<Inner: WriteStr + WriteLayered> {
     /// The wrapped byte stream.
     pub(crate) inner: Inner,
 
@@ -74,6 +78,60 @@ impl<Inner: Write> TextWriter<Utf8Writer<LayeredWriter<Inner>>> {
     pub fn with_crlf_compatibility(inner: Inner) -> Self {
         Self::from_utf8_with_crlf_compatibility(Utf8Writer::new(LayeredWriter::new(inner)))
     }
+
+    /// Like `new`, but permits U+C (FF) as a page break.
+    ///
+    /// A page break is only permitted at the beginning of the stream or
+    /// immediately after a newline, and must be immediately followed by a
+    /// newline. That is, it must be on a line by itself. U+C in any other
+    /// position is still diagnosed as an error.
+    #[inline]
+    pub fn with_form_feed_compatibility(inner: Inner) -> Self {
+        Self::from_utf8_with_form_feed_compatibility(Utf8Writer::new(LayeredWriter::new(inner)))
+    }
+
+    /// Like `new`, but inserts newlines to wrap lines wider than `columns`
+    /// display columns, such as for 72-column email bodies.
+    ///
+    /// Lines are broken at whitespace where possible, and the whitespace at
+    /// the break is removed. A word wider than `columns` is broken between
+    /// grapheme clusters; a grapheme cluster, such as a ZWJ emoji sequence,
+    /// is never split.
+    ///
+    /// Output is held back until the end of each line, or until a flush, so
+    /// that the break positions can be chosen. This mode doesn't translate
+    /// newlines into "\r\n"; inserted newlines are plain "\n"s, like the
+    /// rest of the stream.
+    #[inline]
+    pub fn with_wrap(inner: Inner, columns: usize) -> Self {
+        Self::from_utf8_with_wrap(Utf8Writer::new(LayeredWriter::new(inner)), columns)
+    }
+
+    /// Like `new`, but appends a newline when the stream is closed, if it
+    /// doesn't already end in one, instead of failing.
+    ///
+    /// Closing still fails if the stream ends after a ZWJ or Prepend, or
+    /// within an escape sequence, since appending a newline doesn't fix
+    /// those.
+    #[inline]
+    pub fn with_auto_final_newline(inner: Inner) -> Self {
+        Self::from_utf8_with_auto_final_newline(Utf8Writer::new(LayeredWriter::new(inner)))
+    }
+
+    /// Like `new`, but permits the control codes in `allowed_controls`, such
+    /// as U+B (VT) or U+C (FF), to pass through verbatim. All other control
+    /// codes are still diagnosed as errors.
+    ///
+    /// Basic Text doesn't assign any meaning to these control codes, so the
+    /// caller is responsible for ensuring that the resulting stream is still
+    /// meaningful to its consumers.
+    #[inline]
+    pub fn with_allowed_controls(inner: Inner, allowed_controls: &[char]) -> Self {
+        Self::from_utf8_with_allowed_controls(
+            Utf8Writer::new(LayeredWriter::new(inner)),
+            allowed_controls,
+        )
+    }
 }
 
 impl<Inner: WriteStr + WriteLayered> TextWriter<Inner> {
@@ -115,6 +173,60 @@ impl<Inner: WriteStr + WriteLayered> TextWriter<Inner> {
         }
     }
 
+    /// Like `from_utf8`, but permits U+C (FF) as a page break.
+    ///
+    /// A page break is only permitted at the beginning of the stream or
+    /// immediately after a newline, and must be immediately followed by a
+    /// newline. That is, it must be on a line by itself. U+C in any other
+    /// position is still diagnosed as an error.
+    #[inline]
+    pub fn from_utf8_with_form_feed_compatibility(inner: Inner) -> Self {
+        Self {
+            inner,
+            output: TextOutput::with_form_feed_compatibility(),
+        }
+    }
+
+    /// Like `from_utf8`, but inserts newlines to wrap lines wider than
+    /// `columns` display columns. See [`with_wrap`] for details.
+    ///
+    /// [`with_wrap`]: TextWriter::with_wrap
+    #[inline]
+    pub fn from_utf8_with_wrap(inner: Inner, columns: usize) -> Self {
+        Self {
+            inner,
+            output: TextOutput::with_wrap(columns),
+        }
+    }
+
+    /// Like `from_utf8`, but appends a newline when the stream is closed, if
+    /// it doesn't already end in one, instead of failing. See
+    /// [`with_auto_final_newline`] for details.
+    ///
+    /// [`with_auto_final_newline`]: TextWriter::with_auto_final_newline
+    #[inline]
+    pub fn from_utf8_with_auto_final_newline(inner: Inner) -> Self {
+        Self {
+            inner,
+            output: TextOutput::with_auto_final_newline(),
+        }
+    }
+
+    /// Like `from_utf8`, but permits the control codes in `allowed_controls`,
+    /// such as U+B (VT) or U+C (FF), to pass through verbatim. All other
+    /// control codes are still diagnosed as errors.
+    ///
+    /// Basic Text doesn't assign any meaning to these control codes, so the
+    /// caller is responsible for ensuring that the resulting stream is still
+    /// meaningful to its consumers.
+    #[inline]
+    pub fn from_utf8_with_allowed_controls(inner: Inner, allowed_controls: &[char]) -> Self {
+        Self {
+            inner,
+            output: TextOutput::with_allowed_controls(allowed_controls),
+        }
+    }
+
     /// Flush and close the underlying stream and return the underlying
     /// stream object.
     #[inline]
@@ -122,11 +234,71 @@ impl<Inner: WriteStr + WriteLayered> TextWriter<Inner> {
         TextOutput::close_into_inner(self)
     }
 
+    /// Flush the underlying stream and return the underlying stream object,
+    /// without requiring the stream to end with a newline.
+    ///
+    /// This is useful for output such as interactive prompts, which are
+    /// followed by user input rather than a newline. Ending in an incomplete
+    /// escape sequence, or after a ZWJ or Prepend, is still an error.
+    #[inline]
+    pub fn finish_without_newline(self) -> io::Result<Inner> {
+        TextOutput::finish_without_newline(self)
+    }
+
     /// Return the underlying stream object.
     #[inline]
     pub fn abandon_into_inner(self) -> Inner {
         TextOutput::abandon_into_inner(self)
     }
+
+    /// Return a reference to the underlying stream object.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the underlying stream object.
+    ///
+    /// Output may be buffered in this `TextWriter` and not yet written to
+    /// the underlying stream. Writing to the underlying stream directly
+    /// bypasses the checks and state of this `TextWriter`, so the resulting
+    /// stream may not be valid Basic Text.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Return the counts of the output written to the underlying stream so
+    /// far.
+    #[inline]
+    pub fn written(&self) -> WriteStats {
+        self.output.stats
+    }
+
+    /// Start counting the extended grapheme clusters written, in the
+    /// `graphemes` field of [`written`].
+    ///
+    /// This is off by default, as it requires segmenting all the output.
+    ///
+    /// [`written`]: Self::written
+    #[inline]
+    pub fn count_graphemes(&mut self) {
+        self.output.count_graphemes();
+    }
+
+    /// End the current Basic Text document and flush the underlying stream,
+    /// so that a new, self-contained document can be written to the same
+    /// underlying stream.
+    ///
+    /// As with `close`, this fails if the document doesn't end with a
+    /// newline. Afterward, the next write must begin with a starter, and the
+    /// new document must end with a newline, as with a newly constructed
+    /// `TextWriter`. The mode options are preserved. Errors are still fatal,
+    /// as the underlying stream is abandoned when an error is reported.
+    #[inline]
+    pub fn finish_document(&mut self) -> io::Result<()> {
+        TextOutput::finish_document(self)
+    }
 }
 
 #[cfg(feature = "terminal-io")]
@@ -180,6 +352,11 @@ impl<Inner: WriteStr + WriteLayered> WriteStr for TextWriter<Inner> {
 }
 
 impl<Inner: WriteStr + WriteLayered> WriteText for TextWriter<Inner> {
+    #[inline]
+    fn write_text(&mut self, s: &TextStr) -> io::Result<()> {
+        TextOutput::write_text(self, s)
+    }
+
     #[inline]
     fn write_text_substr(&mut self, s: &TextSubstr) -> io::Result<()> {
         TextOutput::write_text_substr(self, s)
@@ -261,7 +438,18 @@ impl<Inner: WriteStr + WriteLayered + AsHandleOrSocket> AsHandleOrSocket for Tex
     }
 }
 
-impl<Inner: Debug> Debug for TextWriter<Inner> {
+impl<Inner: WriteStr + WriteLayered> Drop for TextWriter<Inner> {
+    fn drop(&mut self) {
+        // If the stream failed, or we're unwinding, or the `TextOutput` is
+        // about to panic, abandon the inner stream so that it doesn't
+        // panic on its own about not being closed.
+        if !self.output.drops_cleanly() {
+            self.inner.abandon();
+        }
+    }
+}
+
+impl<Inner: WriteStr + WriteLayered + Debug> Debug for TextWriter<Inner> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut b = f.debug_struct("TextWriter");
         b.field("inner", &self.inner);
@@ -270,8 +458,16 @@ impl<Inner: Debug> Debug for TextWriter<Inner> {
 }
 
 #[cfg(test)]
-fn translate_via_layered_writer(bytes: &[u8]) -> io::Result<String> {
-    let mut writer = TextWriter::new(Vec::<u8>::new());
+type TestWriter = TextWriter<Utf8Writer<LayeredWriter<Vec<u8>>>>;
+
+/// Write `bytes` to `writer`, end the text stream with `finish`, and return
+/// what was written to the underlying `Vec`.
+#[cfg(test)]
+fn translate_with_writer(
+    mut writer: TestWriter,
+    bytes: &[u8],
+    finish: impl FnOnce(TestWriter) -> io::Result<Utf8Writer<LayeredWriter<Vec<u8>>>>,
+) -> io::Result<String> {
     match writer.write_all(bytes) {
         Ok(()) => (),
         Err(err) => {
@@ -279,13 +475,19 @@ fn translate_via_layered_writer(bytes: &[u8]) -> io::Result<String> {
             return Err(err);
         }
     }
-    let inner = writer
-        .close_into_inner()?
-        .close_into_inner()?
-        .close_into_inner()?;
+    let inner = finish(writer)?.close_into_inner()?.close_into_inner()?;
     Ok(String::from_utf8(inner).unwrap())
 }
 
+#[cfg(test)]
+fn translate_via_layered_writer(bytes: &[u8]) -> io::Result<String> {
+    translate_with_writer(
+        TextWriter::new(Vec::new()),
+        bytes,
+        TextWriter::close_into_inner,
+    )
+}
+
 #[cfg(test)]
 fn translate_str_via_layered_writer(s: &str) -> io::Result<String> {
     let mut writer = TextWriter::new(Vec::<u8>::new());
@@ -379,6 +581,401 @@ fn test_ff() {
     test_error(b"hello world\x0c\n");
 }
 
+#[test]
+fn test_ff_compatibility() {
+    let translate = |bytes: &[u8]| {
+        translate_with_writer(
+            TextWriter::with_form_feed_compatibility(Vec::new()),
+            bytes,
+            TextWriter::close_into_inner,
+        )
+    };
+
+    assert_eq!(translate(b"\x0c\n").unwrap(), "\x0c\n");
+    assert_eq!(
+        translate(b"hello\n\x0c\nworld\n").unwrap(),
+        "hello\n\x0c\nworld\n"
+    );
+    assert_eq!(translate(b"\x0c\n\x0c\n").unwrap(), "\x0c\n\x0c\n");
+    translate(b"\x0c").unwrap_err();
+    translate(b"\x0c\x0c\n").unwrap_err();
+    translate(b"\x0chello\n").unwrap_err();
+    translate(b"hello\x0c\n").unwrap_err();
+    translate(b"hello\n\x0cworld\n").unwrap_err();
+}
+
+#[test]
+fn test_short_write() {
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    assert_eq!(writer.write(b"hello\nworld\x07\n").unwrap(), 11);
+    writer.write(b"\x07\n").unwrap_err();
+    writer.abandon();
+
+    // With normalization, the valid prefix ends at a newline.
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    assert_eq!(writer.write("A\u{30a}\nhello\x07".as_bytes()).unwrap(), 4);
+    writer.write(b"hello\x07").unwrap_err();
+    writer.abandon();
+
+    // With CRLF compatibility, offsets refer to the input.
+    let mut writer = TextWriter::with_crlf_compatibility(Vec::<u8>::new());
+    assert_eq!(writer.write(b"a\nb\x07").unwrap(), 3);
+    writer.write(b"\x07").unwrap_err();
+    writer.abandon();
+
+    // An error at the start of the data is reported immediately.
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write(b"\x07hello\n").unwrap_err();
+    writer.abandon();
+}
+
+#[test]
+fn test_allowed_controls() {
+    let translate = |bytes: &[u8]| {
+        translate_with_writer(
+            TextWriter::with_allowed_controls(Vec::new(), &['\x0b', '\x0c']),
+            bytes,
+            TextWriter::close_into_inner,
+        )
+    };
+
+    assert_eq!(translate(b"a\tb\n").unwrap(), "a\tb\n");
+    assert_eq!(translate(b"a\x0bb\n").unwrap(), "a\x0bb\n");
+    assert_eq!(translate(b"a\x0cb\x0c\n").unwrap(), "a\x0cb\x0c\n");
+    translate(b"a\x07b\n").unwrap_err();
+    translate(b"a\x1b[mb\n").unwrap_err();
+}
+
+#[test]
+fn test_finish_without_newline() {
+    let translate = |bytes: &[u8]| {
+        translate_with_writer(
+            TextWriter::new(Vec::new()),
+            bytes,
+            TextWriter::finish_without_newline,
+        )
+    };
+
+    assert_eq!(translate(b"").unwrap(), "");
+    assert_eq!(translate(b"Name: ").unwrap(), "Name: ");
+    assert_eq!(translate(b"hello\n").unwrap(), "hello\n");
+    translate("a\u{200d}".as_bytes()).unwrap_err();
+    translate(b"a\x1b[").unwrap_err();
+}
+
+#[test]
+fn test_write_text() {
+    let text = |s| TextStr::from_text(s).unwrap();
+    let parts = [text("hello "), text("w\u{f6}rld\n"), text(""), text("x")];
+
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    let mut substr_writer = TextWriter::new(Vec::<u8>::new());
+    for part in parts {
+        writer.write_text(part).unwrap();
+        substr_writer.write_text_substr(part.as_ref()).unwrap();
+    }
+    assert_eq!(writer.written(), substr_writer.written());
+    writer.close().unwrap_err();
+    substr_writer.close().unwrap_err();
+
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_text(text("hello\n")).unwrap();
+    writer.write_text(text("world\n")).unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "hello\nworld\n");
+
+    let mut writer = TextWriter::with_crlf_compatibility(Vec::<u8>::new());
+    writer.write_text(text("hello\nworld\n")).unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "hello\r\nworld\r\n");
+}
+
+#[test]
+fn test_write_text_after_error() {
+    let text = |s| TextStr::from_text(s).unwrap();
+
+    // A deferred error from a partial write is reported.
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    assert_eq!(writer.write(b"ok\x07").unwrap(), 2);
+    writer.write_text(text("more\n")).unwrap_err();
+    writer.abandon();
+
+    // A page break must be followed by a newline.
+    let mut writer = TextWriter::with_form_feed_compatibility(Vec::<u8>::new());
+    writer.write_all(b"a\n\x0c").unwrap();
+    writer.write_text(text("bc\n")).unwrap_err();
+    writer.abandon();
+
+    let mut writer = TextWriter::with_form_feed_compatibility(Vec::<u8>::new());
+    writer.write_all(b"a\n\x0c").unwrap();
+    writer.write_text(text("\nbc\n")).unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "a\n\x0c\nbc\n");
+}
+
+#[test]
+fn test_write_text_line() {
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer
+        .write_text_line(TextStr::from_text("hello").unwrap())
+        .unwrap();
+    writer
+        .write_text_line(TextStr::from_text("").unwrap())
+        .unwrap();
+    writer
+        .write_text_line(TextStr::from_text("\u{1f415}\u{200d}\u{1f9ba}").unwrap())
+        .unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(inner).unwrap(),
+        "hello\n\n\u{1f415}\u{200d}\u{1f9ba}\n"
+    );
+}
+
+#[test]
+fn test_auto_final_newline() {
+    let translate = |bytes: &[u8]| {
+        translate_with_writer(
+            TextWriter::with_auto_final_newline(Vec::new()),
+            bytes,
+            TextWriter::close_into_inner,
+        )
+    };
+
+    assert_eq!(translate(b"").unwrap(), "");
+    assert_eq!(translate(b"\n").unwrap(), "\n");
+    assert_eq!(translate(b"hello").unwrap(), "hello\n");
+    assert_eq!(translate(b"hello\n").unwrap(), "hello\n");
+    assert_eq!(translate(b"hello\nworld").unwrap(), "hello\nworld\n");
+    translate("a\u{200d}".as_bytes()).unwrap_err();
+    translate(b"a\x1b").unwrap_err();
+    translate(b"a\x1b[").unwrap_err();
+
+    let mut writer = TextWriter::with_auto_final_newline(Vec::<u8>::new());
+    writer.write_all(b"hello").unwrap();
+    writer.close().unwrap();
+}
+
+#[test]
+fn test_write_text_substrs() {
+    let parts = ["hello", " ", "world\n"]
+        .iter()
+        .map(|s| TextSubstr::from_text(s).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_text_substrs(&parts).unwrap();
+    writer.write_text_substrs(&[]).unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "hello world\n");
+}
+
+#[test]
+fn test_wrap() {
+    fn translate(bytes: &[u8], columns: usize) -> String {
+        let mut writer = TextWriter::with_wrap(Vec::<u8>::new(), columns);
+        writer.write_all(bytes).unwrap();
+        let inner = writer
+            .close_into_inner()
+            .unwrap()
+            .close_into_inner()
+            .unwrap()
+            .close_into_inner()
+            .unwrap();
+        String::from_utf8(inner).unwrap()
+    }
+
+    assert_eq!(translate(b"", 10), "");
+    assert_eq!(translate(b"hello world\n", 11), "hello world\n");
+    assert_eq!(translate(b"hello world\n", 10), "hello\nworld\n");
+    assert_eq!(translate(b"hello  world\n", 8), "hello\nworld\n");
+    assert_eq!(
+        translate(b"the quick brown fox\njumps\n", 10),
+        "the quick\nbrown fox\njumps\n"
+    );
+    assert_eq!(translate(b"abcdefgh\n", 3), "abc\ndef\ngh\n");
+
+    // Wide characters take two columns.
+    assert_eq!(
+        translate("\u{4e00}\u{4e8c}\u{4e09}\n".as_bytes(), 4),
+        "\u{4e00}\u{4e8c}\n\u{4e09}\n"
+    );
+
+    // Grapheme clusters aren't split.
+    assert_eq!(
+        translate("e\u{301}\u{301}e\u{301}\n".as_bytes(), 1),
+        "\u{e9}\u{301}\n\u{e9}\n"
+    );
+    let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+    assert_eq!(
+        translate(format!("ab{}\n", family).as_bytes(), 2),
+        format!("ab\n{}\n", family)
+    );
+
+    // Breaks may be chosen across writes.
+    let mut writer = TextWriter::with_wrap(Vec::<u8>::new(), 10);
+    writer.write_all(b"hello wo").unwrap();
+    writer.write_all(b"rld\n").unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "hello\nworld\n");
+
+    // A flush writes out held-back output.
+    let mut writer = TextWriter::with_wrap(Vec::<u8>::new(), 10);
+    writer.write_all(b"Name: ").unwrap();
+    writer.flush().unwrap();
+    writer.write_all(b"alexander\n").unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "Name: \nalexander\n");
+}
+
+#[test]
+fn test_finish_document() {
+    let mut writer = TextWriter::with_crlf_compatibility(Vec::<u8>::new());
+    writer.write_all(b"one\n").unwrap();
+    writer.finish_document().unwrap();
+    writer.write_all(b"two\n").unwrap();
+    writer.finish_document().unwrap();
+    writer.write_all(b"three\n").unwrap();
+
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "one\r\ntwo\r\nthree\r\n");
+
+    // An incomplete document is an error.
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_all(b"three").unwrap();
+    writer.finish_document().unwrap_err();
+
+    // Unless a final newline is appended.
+    let mut writer = TextWriter::with_auto_final_newline(Vec::<u8>::new());
+    writer.write_all(b"three").unwrap();
+    writer.finish_document().unwrap();
+    writer.write_all(b"four").unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(String::from_utf8(inner).unwrap(), "three\nfour\n");
+
+    // Each document must begin with a starter.
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_all(b"one\n").unwrap();
+    writer.finish_document().unwrap();
+    writer.write_all("\u{301}\n".as_bytes()).unwrap_err();
+}
+
+#[test]
+fn test_written() {
+    let mut writer = TextWriter::with_crlf_compatibility(Vec::<u8>::new());
+    writer.count_graphemes();
+    assert_eq!(writer.written(), WriteStats::new());
+    writer.write_all(b"hello\n").unwrap();
+    // The U+302 extends the grapheme cluster from the previous write.
+    writer.write_all("e\u{301}".as_bytes()).unwrap();
+    writer.write_all("\u{302}x\n".as_bytes()).unwrap();
+    assert_eq!(
+        writer.written(),
+        WriteStats {
+            bytes: 14,
+            graphemes: 9,
+            lines: 2,
+        }
+    );
+    writer.close().unwrap();
+}
+
+#[test]
+fn test_written_without_graphemes() {
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_all("e\u{301}\nx\n".as_bytes()).unwrap();
+    assert_eq!(
+        writer.written(),
+        WriteStats {
+            bytes: 5,
+            graphemes: 0,
+            lines: 2,
+        }
+    );
+    writer.close().unwrap();
+}
+
+#[test]
+fn test_drop_after_error() {
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_all(b"hello\x07").unwrap_err();
+    drop(writer);
+}
+
+#[test]
+fn test_drop_while_unwinding() {
+    // Dropping an unfinished stream while unwinding from an unrelated panic
+    // shouldn't panic again, which would abort.
+    let result = std::panic::catch_unwind(|| {
+        let mut writer = TextWriter::new(Vec::<u8>::new());
+        writer.write_all(b"hello").unwrap();
+        panic!("unrelated");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "strict text stream not ended with newline")]
+fn test_drop_unfinished() {
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    writer.write_all(b"hello").unwrap();
+    drop(writer);
+}
+
 #[test]
 fn test_del() {
     test_error(b"\x7f");
@@ -593,3 +1190,19 @@ fn test_dddha() {
 
 // TODO: Test Stream-Safe
 // TODO: test for nonstarter after push
+
+#[test]
+fn test_get_ref() {
+    let mut writer = TextWriter::new(Vec::<u8>::new());
+    let _: &Utf8Writer<LayeredWriter<Vec<u8>>> = writer.get_ref();
+    let _: &mut Utf8Writer<LayeredWriter<Vec<u8>>> = writer.get_mut();
+    writer.write_all(b"world\n").unwrap();
+    let inner = writer
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap()
+        .close_into_inner()
+        .unwrap();
+    assert_eq!(inner, b"world\n");
+}
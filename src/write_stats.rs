@@ -0,0 +1,34 @@
+//! Counters describing the Basic Text written to an output stream.
+
+/// Counts of the Basic Text a [`TextWriter`] has written to its underlying
+/// stream.
+///
+/// [`TextWriter`]: crate::TextWriter
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteStats {
+    /// The number of bytes written, including any newlines translated into
+    /// "\r\n" in CRLF compatibility mode, but not including a U+FEFF (BOM)
+    /// written in BOM compatibility mode.
+    pub bytes: u64,
+
+    /// The number of extended grapheme clusters written. This is only
+    /// counted after [`TextWriter::count_graphemes`] is called.
+    ///
+    /// [`TextWriter::count_graphemes`]: crate::TextWriter::count_graphemes
+    pub graphemes: u64,
+
+    /// The number of newlines written.
+    pub lines: u64,
+}
+
+impl WriteStats {
+    /// Construct a new instance of `WriteStats` with all counts zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            bytes: 0,
+            graphemes: 0,
+            lines: 0,
+        }
+    }
+}
@@ -3,6 +3,15 @@ use std::io;
 use utf8_io::WriteStr;
 
 /// Add a convenience method for reading into `TextStr`.
+///
+/// All of the methods have default implementations which forward to
+/// `write_text_substr`, which in turn forwards to `WriteStr::write_str`.
+/// Implementations which track Basic Text state, such as [`TextWriter`],
+/// override `write_text_substr` to skip re-validating the data, and may
+/// override `write_text` to take advantage of a `TextStr` beginning with a
+/// starter and ending at a valid end.
+///
+/// [`TextWriter`]: crate::TextWriter
 pub trait WriteText: WriteStr {
     /// Like `WriteStr::write_str` but writes from a `TextStr`.
     #[inline]
@@ -10,11 +19,35 @@ pub trait WriteText: WriteStr {
         self.write_text_substr(buf.as_ref())
     }
 
+    /// Write `buf` followed by a '\n'.
+    ///
+    /// A `TextStr` never ends with a ZWJ or Prepend, so a newline can always
+    /// follow it, and after this the stream is at a point where it can be
+    /// closed.
+    #[inline]
+    fn write_text_line(&mut self, buf: &TextStr) -> io::Result<()> {
+        self.write_text(buf)?;
+        self.write_str("\n")
+    }
+
     /// Like `WriteStr::write_str` but writes from a `TextSubstr`.
     #[inline]
     fn write_text_substr(&mut self, buf: &TextSubstr) -> io::Result<()> {
         default_write_text_substr(self, buf)
     }
+
+    /// Write each of `parts` in order, as if by `write_text_substr`.
+    ///
+    /// Implementations may coalesce the parts into fewer underlying writes.
+    /// If writing fails, the error from the first failing part is returned,
+    /// and the parts after it are not written.
+    #[inline]
+    fn write_text_substrs(&mut self, parts: &[&TextSubstr]) -> io::Result<()> {
+        for part in parts {
+            self.write_text_substr(part)?;
+        }
+        Ok(())
+    }
 }
 
 // There is no `WriteTextLayered` because none of the `WriteLayered` functions
@@ -1,4 +1,4 @@
-use basic_text::{text, BufReadText};
+use basic_text::{text, BufReadText, RepairStats};
 use std::io::BufReader;
 
 #[test]
@@ -64,3 +64,89 @@ fn buf_read_test_lossy() {
     );
     assert_eq!(lines.next().map(Result::unwrap), None);
 }
+
+#[test]
+fn buf_read_test_numbered() {
+    let input = "red\n\u{200d}orange\nyellow\n";
+    let reader = BufReader::new(input.as_bytes());
+    let mut lines = reader.numbered_text_lines();
+    let (n, line) = lines.next().unwrap();
+    assert_eq!((n, line.unwrap()), (1, text!("red").to_owned()));
+    let (n, line) = lines.next().unwrap();
+    assert_eq!(n, 2);
+    line.unwrap_err();
+    let (n, line) = lines.next().unwrap();
+    assert_eq!((n, line.unwrap()), (3, text!("yellow").to_owned()));
+    assert!(lines.next().is_none());
+
+    let reader = BufReader::new(input.as_bytes());
+    let mut lines = reader.text_lines_lossy();
+    assert_eq!(lines.line_number(), 0);
+    lines.next().unwrap().unwrap();
+    lines.next().unwrap().unwrap();
+    assert_eq!(lines.line_number(), 2);
+    lines.next().unwrap().unwrap();
+    assert!(lines.next().is_none());
+    assert_eq!(lines.line_number(), 3);
+}
+
+#[test]
+fn buf_read_test_audited() {
+    let input = "red\r\norange\u{7}\n\u{200d}yellow\u{200d}\ngreen";
+    let reader = BufReader::new(input.as_bytes());
+    let mut lines = reader.text_lines_audited();
+
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("red").to_owned());
+    assert_eq!(stats.newlines_normalized, 1);
+    assert_eq!(stats.total(), 1);
+
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("orange\u{fffd}").to_owned());
+    assert_eq!(stats.scalars_replaced, 1);
+    assert_eq!(stats.total(), 1);
+
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(
+        line,
+        text!("\u{34f}\u{200d}yellow\u{200d}\u{34f}").to_owned()
+    );
+    assert_eq!(stats.cgjs_inserted, 2);
+    assert_eq!(stats.total(), 2);
+
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("green").to_owned());
+    assert_eq!(stats.newlines_appended, 1);
+    assert_eq!(stats.total(), 1);
+
+    assert!(lines.next().is_none());
+
+    let reader = BufReader::new("clean\n".as_bytes());
+    let mut lines = reader.text_lines_audited();
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("clean").to_owned());
+    assert_eq!(stats, RepairStats::new());
+
+    // Only a U+FEFF at the start of the stream is a BOM.
+    let reader = BufReader::new("a\n\u{feff}b\n".as_bytes());
+    let mut lines = reader.text_lines_audited();
+    let (line, _stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("a").to_owned());
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("\u{2060}b").to_owned());
+    assert_eq!(stats.scalars_replaced, 1);
+    assert!(lines.next().is_none());
+
+    // Invalid UTF-8 is repaired rather than reported as an error.
+    let reader = BufReader::new(&b"a\n\xffb\nc\n"[..]);
+    let mut lines = reader.text_lines_audited();
+    let (line, _stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("a").to_owned());
+    let (line, stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("\u{fffd}b").to_owned());
+    assert_eq!(stats.scalars_replaced, 1);
+    assert_eq!(stats.total(), 1);
+    let (line, _stats) = lines.next().unwrap().unwrap();
+    assert_eq!(line, text!("c").to_owned());
+    assert!(lines.next().is_none());
+}
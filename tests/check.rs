@@ -0,0 +1,154 @@
+mod disallowed_scalar_values;
+
+use basic_text::{
+    check_basic_text_char, first_violation, is_basic_text, is_basic_text_end, is_basic_text_start,
+    is_basic_text_substr, stream_safe_boundaries, BasicTextError, StreamSafeNfc,
+};
+use disallowed_scalar_values::DISALLOWED_SCALAR_VALUES;
+
+#[test]
+fn test_check_basic_text_char() {
+    for c in ['a', '\n', '\t', ' ', 'é', '☃', '\u{34f}', '\u{fffd}'] {
+        assert!(
+            check_basic_text_char(c).is_ok(),
+            "scalar value {:?} was rejected",
+            c
+        );
+    }
+
+    for c in &DISALLOWED_SCALAR_VALUES {
+        assert!(
+            check_basic_text_char(*c).is_err(),
+            "disallowed scalar value {:?} was not rejected",
+            c
+        );
+    }
+
+    assert!(matches!(
+        check_basic_text_char('\u{7}'),
+        Err(BasicTextError::ControlCode('\u{7}'))
+    ));
+    assert!(matches!(
+        check_basic_text_char('\u{feff}'),
+        Err(BasicTextError::UnneededBOM)
+    ));
+    match check_basic_text_char('\u{fb01}') {
+        Err(BasicTextError::Replacement { yes, no }) => {
+            assert_eq!(&*yes, &['f', 'i']);
+            assert_eq!(no, '\u{fb01}');
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_is_basic_text_start_end() {
+    assert!(is_basic_text_start('a'));
+    assert!(is_basic_text_end('a'));
+
+    // Non-starters can't begin Basic Text.
+    assert!(!is_basic_text_start('\u{301}'));
+    assert!(is_basic_text_end('\u{301}'));
+
+    // ZWJ can neither begin nor end Basic Text.
+    assert!(!is_basic_text_start('\u{200d}'));
+    assert!(!is_basic_text_end('\u{200d}'));
+
+    // Prepended concatenation marks can't end Basic Text.
+    assert!(is_basic_text_start('\u{600}'));
+    assert!(!is_basic_text_end('\u{600}'));
+}
+
+#[test]
+fn test_is_basic_text() {
+    assert!(is_basic_text(""));
+    assert!(is_basic_text("hello\n"));
+    assert!(!is_basic_text("\u{301}hello"));
+    assert!(!is_basic_text("hello\u{200d}"));
+    assert!(!is_basic_text("e\u{301}"));
+    assert!(!is_basic_text("\u{7}"));
+
+    assert!(is_basic_text_substr("\u{301}hello\u{200d}"));
+    assert!(!is_basic_text_substr("e\u{301}"));
+    assert!(!is_basic_text_substr("\u{7}"));
+}
+
+#[test]
+fn test_stream_safe_nfc() {
+    let normalize = |s: &str| StreamSafeNfc::new(s.chars()).collect::<String>();
+
+    assert_eq!(normalize(""), "");
+    assert_eq!(normalize("hello\n"), "hello\n");
+    assert_eq!(normalize("A\u{30a}"), "\u{c5}");
+    assert_eq!(normalize("\u{f900}"), "\u{8c48}\u{fe00}");
+    assert_eq!(normalize("a\u{378}b"), "a\u{34f}\u{378}\u{34f}b");
+    assert_eq!(
+        normalize(&format!("x{}", "\u{301}".repeat(31))),
+        format!("x{}\u{34f}\u{301}", "\u{301}".repeat(30))
+    );
+}
+
+#[test]
+fn test_first_violation() {
+    for s in [
+        "",
+        "hello\n",
+        "\u{301}hello",
+        "hello\u{200d}",
+        "e\u{301}",
+        "\u{7}",
+        "a\u{1b}[m",
+        "x\u{200d}\u{7}",
+    ] {
+        assert_eq!(first_violation(s).is_none(), is_basic_text(s), "{:?}", s);
+    }
+
+    assert!(matches!(
+        first_violation("\u{301}hello"),
+        Some((0, BasicTextError::NonStarter('\u{301}')))
+    ));
+    assert!(matches!(
+        first_violation("hello\u{200d}"),
+        Some((5, BasicTextError::NonEnding('\u{200d}')))
+    ));
+    assert!(matches!(
+        first_violation("hello e\u{301}"),
+        Some((6, BasicTextError::NotStreamSafeNfc))
+    ));
+    assert!(matches!(
+        first_violation("ab\u{7}c\u{1b}"),
+        Some((2, BasicTextError::ControlCode('\u{7}')))
+    ));
+    assert!(matches!(
+        first_violation("ab\u{1b}[m"),
+        Some((2, BasicTextError::Escape))
+    ));
+    assert!(matches!(
+        first_violation("e\u{301}\u{7}"),
+        Some((0, BasicTextError::NotStreamSafeNfc))
+    ));
+    assert!(matches!(
+        first_violation("ab\u{feff}"),
+        Some((2, BasicTextError::UnneededBOM))
+    ));
+}
+
+#[test]
+fn test_stream_safe_boundaries() {
+    let boundaries = |s: &str| stream_safe_boundaries(s).collect::<Vec<_>>();
+    assert_eq!(boundaries(""), []);
+    assert_eq!(boundaries("abc"), []);
+    assert_eq!(boundaries("a\nb\n"), [2, 4]);
+    assert_eq!(boundaries("a\u{34f}\u{301}\nb"), [3, 6]);
+    assert_eq!(boundaries("a\u{34f}\u{50000}\n"), [8]);
+
+    let s = "A\u{30a}\n\u{34f}\u{301}\u{327}x\u{34f}\u{50000}\u{301}\n\u{2126}";
+    let whole = StreamSafeNfc::new(s.chars()).collect::<String>();
+    let mut chunked = String::new();
+    let mut start = 0;
+    for end in stream_safe_boundaries(s).chain(Some(s.len())) {
+        chunked.extend(StreamSafeNfc::new(s[start..end].chars()));
+        start = end;
+    }
+    assert_eq!(chunked, whole);
+}
@@ -1,4 +1,4 @@
-use basic_text::{text, text_substr};
+use basic_text::{text, text_concat, text_len, text_substr, TextStr};
 use std::io::{sink, Write};
 
 #[test]
@@ -13,3 +13,30 @@ fn text_substr_macro() {
     writeln!(s, "{}", text_substr!("hello world")).unwrap();
     writeln!(s, "{}", text_substr!("\u{200d}hello world\u{200d}")).unwrap();
 }
+
+#[test]
+fn text_len_macro() {
+    const EMPTY: usize = text_len!("");
+    const HELLO: usize = text_len!("hello");
+    const COMBINED: usize = text_len!("x\u{301}\u{1f468}\u{200d}\u{1f469}\n");
+
+    assert_eq!(EMPTY, 0);
+    assert_eq!(HELLO, 5);
+    assert_eq!(COMBINED, 3);
+
+    let array = [0_u8; text_len!("abc")];
+    assert_eq!(array.len(), 3);
+}
+
+#[test]
+fn text_concat_macro() {
+    let empty: &'static TextStr = text_concat!();
+    assert_eq!(empty, "");
+    assert_eq!(text_concat!("hello"), "hello");
+    assert_eq!(text_concat!("hello", " ", "world\n",), "hello world\n");
+    assert_eq!(text_concat!("x", "\u{301}"), "x\u{301}");
+    assert_eq!(
+        text_concat!("\u{1f415}\u{200d}", "\u{1f9ba}"),
+        "\u{1f415}\u{200d}\u{1f9ba}"
+    );
+}
@@ -0,0 +1,102 @@
+//! Surrogate code points can't appear in Basic Text. Test that the
+//! constructors which can encounter them, directly or in encoded form,
+//! reject or replace them consistently.
+
+use basic_text::{TextStr, TextString};
+
+#[test]
+fn utf16_paired_surrogates() {
+    let v = "a🐕b".encode_utf16().collect::<Vec<u16>>();
+    assert_eq!(TextString::from_utf16_lossy(&v), "a🐕b");
+}
+
+#[test]
+fn utf16_lone_high_surrogate() {
+    assert_eq!(TextString::from_utf16_lossy(&[0xd83d]), "\u{fffd}");
+    assert_eq!(
+        TextString::from_utf16_lossy(&[0x61, 0xd83d, 0x62]),
+        "a\u{fffd}b"
+    );
+    assert_eq!(TextString::from_utf16_lossy(&[0x61, 0xd83d]), "a\u{fffd}");
+}
+
+#[test]
+fn utf16_lone_low_surrogate() {
+    assert_eq!(TextString::from_utf16_lossy(&[0xdc15]), "\u{fffd}");
+    assert_eq!(
+        TextString::from_utf16_lossy(&[0x61, 0xdc15, 0x62]),
+        "a\u{fffd}b"
+    );
+}
+
+#[test]
+fn utf16_reversed_surrogates() {
+    // A low surrogate followed by a high surrogate is two unpaired
+    // surrogates, each replaced by its own U+FFFD.
+    assert_eq!(
+        TextString::from_utf16_lossy(&[0xdc15, 0xd83d]),
+        "\u{fffd}\u{fffd}"
+    );
+}
+
+#[test]
+fn utf16_repairs() {
+    let v = "\u{feff}A\u{30a}\r\n".encode_utf16().collect::<Vec<u16>>();
+    assert_eq!(TextString::from_utf16_lossy(&v), "\u{2060}\u{c5}\n");
+    assert_eq!(
+        TextString::from_utf16_lossy(&[0x301, 0xd83d]),
+        "\u{34f}\u{301}\u{fffd}"
+    );
+}
+
+#[test]
+fn encoded_surrogates_rejected() {
+    // The generalized UTF-8 (WTF-8) encoding of U+D83D.
+    let bytes = b"a\xed\xa0\xbdb";
+    TextStr::from_text_bytes(bytes).unwrap_err();
+    TextString::from_text_vec(bytes.to_vec()).unwrap_err();
+}
+
+#[test]
+fn encoded_surrogates_replaced() {
+    // Encoded surrogates are invalid UTF-8, and each byte of one is replaced,
+    // following the usual UTF-8 lossy conversion.
+    let bytes = b"a\xed\xa0\xbdb";
+    assert_eq!(
+        TextString::from_text_bytes_lossy(bytes).as_str(),
+        "a\u{fffd}\u{fffd}\u{fffd}b"
+    );
+    assert_eq!(
+        TextString::from_text_vec_lossy(bytes.to_vec()),
+        "a\u{fffd}\u{fffd}\u{fffd}b"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn os_str_encoded_surrogates() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let s = OsStr::from_bytes(b"a\xed\xa0\xbdb");
+    basic_text::from_os_str(s).unwrap_err();
+    assert_eq!(
+        basic_text::from_os_str_lossy(s),
+        "a\u{fffd}\u{fffd}\u{fffd}b"
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn os_str_unpaired_surrogates() {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+
+    let s = OsString::from_wide(&[0x61, 0xd83d, 0x62]);
+    basic_text::from_os_str(&s).unwrap_err();
+    assert_eq!(basic_text::from_os_str_lossy(&s), "a\u{fffd}b");
+    assert_eq!(
+        basic_text::from_os_str_lossy(&s),
+        TextString::from_utf16_lossy(&[0x61, 0xd83d, 0x62])
+    );
+}
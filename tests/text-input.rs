@@ -1,6 +1,7 @@
 mod disallowed_scalar_values;
 
-use basic_text::TextReader;
+use basic_text::{sanitize, TextReader};
+use basic_text_internals::BasicTextChars;
 use disallowed_scalar_values::DISALLOWED_SCALAR_VALUES;
 use std::io::{self, Cursor, Read};
 
@@ -242,3 +243,66 @@ fn test_text_input_end() {
     assert_eq!(to_text("hello\r\nworld"), "hello\nworld\n");
     assert_eq!(to_text("hello\r\nworld\r\n"), "hello\nworld\n");
 }
+
+/// A reader which returns at most one byte at a time.
+struct OneByte<'a>(&'a [u8]);
+
+impl Read for OneByte<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((b, rest)), Some(out)) => {
+                *out = *b;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_ascii_fast_path() {
+    // Plain ASCII runs bypass the state machine, so check that they produce
+    // the same output as the iterator-based translation, including next to
+    // control codes, escapes, and non-ASCII.
+    let long = "fn main() { println!(\"hello\"); }\n".repeat(1000);
+    let inputs = [
+        "",
+        "hello world",
+        "hello\tworld\n",
+        "a\rb\r\nc\x0cd\x0c\ne",
+        "\x1b[31mred\x1b[0m plain\x07 \x1b]0;title\x07text\n",
+        "\u{feff}abc",
+        "abc\u{feff}def",
+        "A\u{30a}bc\u{301}d",
+        "x\u{200d}",
+        "\u{301}abc",
+        "~\x7f ",
+        &long,
+    ];
+    for input in inputs {
+        let expected = BasicTextChars::new(input.chars()).collect::<String>();
+        assert_eq!(to_text(input), expected, "input: {:?}", input);
+
+        // A leading BOM is only recognized if it arrives in a single read.
+        if input.starts_with('\u{feff}') {
+            continue;
+        }
+        let mut s = String::new();
+        TextReader::new(OneByte(input.as_bytes()))
+            .read_to_string(&mut s)
+            .unwrap();
+        assert_eq!(s, expected, "input: {:?}", input);
+    }
+
+    // Repairs next to ASCII runs are reported at the same positions.
+    let (text, repairs) = sanitize("ab\x07cd\r\nef");
+    assert_eq!(text, "ab\u{fffd}cd\nef\n");
+    assert_eq!(
+        repairs
+            .iter()
+            .map(|r| r.byte_range.clone())
+            .collect::<Vec<_>>(),
+        [2..3, 5..7, 9..9]
+    );
+}